@@ -1,11 +1,20 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use sitemaps::diff::Field;
+use sitemaps::error::Error;
+use sitemaps::filter::Filter;
+use sitemaps::merge::ConflictPolicy;
+use sitemaps::sitemap::{ChangeFreq, Priority};
+use sitemaps::w3c_datetime::W3CDateTime;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "Sitemaps")]
 #[command(version = "0.1.0")]
 #[command(about = "Read data from sitemap.xml files", long_about = None)]
 pub(crate) struct Cli {
-    /// Path to sitemap.xml file
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Path to sitemap.xml file, or the path or URL of a robots.txt file listing sitemaps
     #[arg(default_value = "-")]
     pub path: Option<String>,
     /// Include value of <loc> in output
@@ -20,19 +29,183 @@ pub(crate) struct Cli {
     /// Include value of <priority> in output. Sitemaps only.
     #[arg(short, long)]
     pub priority: bool,
+    /// Include the number of <image:image> elements in output. Sitemaps only.
+    #[arg(short, long)]
+    pub images: bool,
     /// Print output table with cell borders.
-    #[arg(short = 'P', long)]
+    #[arg(short = 'P', long, global = true)]
     pub pretty: bool,
     /// Print output table with column headers.
-    #[arg(short = 'H', long, default_value_t = true)]
+    #[arg(short = 'H', long, default_value_t = true, global = true)]
     pub header: bool,
     /// Print output as JSON.
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub json: bool,
     /// Print output as CSV.
-    #[arg(short = 'C', long)]
+    #[arg(short = 'C', long, global = true)]
     pub csv: bool,
     /// Print output as Markdown.
-    #[arg(short = 'm', long)]
+    #[arg(short = 'm', long, global = true)]
     pub markdown: bool,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// Options selecting which entries are output.
+#[derive(Args)]
+#[command(next_help_heading = "Filters")]
+pub(crate) struct FilterArgs {
+    /// Only entries last modified on or after this date or datetime
+    #[arg(long, value_parser = W3CDateTime::new)]
+    pub since: Option<W3CDateTime>,
+    /// Only entries last modified on or before this date or datetime
+    #[arg(long, value_parser = W3CDateTime::new)]
+    pub until: Option<W3CDateTime>,
+    /// Only entries whose <loc> matches a glob pattern, with * and ? wildcards. May be repeated.
+    #[arg(long)]
+    pub glob: Vec<String>,
+    /// Only entries whose <loc> matches a regular expression
+    #[arg(long)]
+    pub regex: Option<String>,
+    /// Only entries whose <loc> is on this host
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Only entries whose <loc> has a path starting with this prefix
+    #[arg(long)]
+    pub path_prefix: Option<String>,
+    /// Only entries with this <changefreq>. May be repeated. Sitemaps only.
+    #[arg(long)]
+    pub freq: Vec<ChangeFreq>,
+    /// Only entries with a <priority> of at least this value. Sitemaps only.
+    #[arg(long)]
+    pub min_priority: Option<Priority>,
+    /// Only entries with a <priority> of at most this value. Sitemaps only.
+    #[arg(long)]
+    pub max_priority: Option<Priority>,
+    /// Only entries that have a value for this field. May be repeated.
+    #[arg(long, value_enum)]
+    pub has: Vec<FieldArg>,
+    /// Only entries that have no value for this field. May be repeated.
+    #[arg(long, value_enum)]
+    pub missing: Vec<FieldArg>,
+}
+
+impl FilterArgs {
+    /// Build the library filter the options describe.
+    pub fn filter(&self) -> Result<Filter, Error> {
+        let mut filter = Filter::new();
+        if let Some(since) = self.since {
+            filter = filter.since(since);
+        }
+        if let Some(until) = self.until {
+            filter = filter.until(until);
+        }
+        for pattern in &self.glob {
+            filter = filter.loc_glob(pattern.as_str());
+        }
+        if let Some(ref regex) = self.regex {
+            filter = filter.loc_regex(regex)?;
+        }
+        if let Some(ref host) = self.host {
+            filter = filter.host(host.as_str());
+        }
+        if let Some(ref prefix) = self.path_prefix {
+            filter = filter.path_prefix(prefix.as_str());
+        }
+        for &freq in &self.freq {
+            filter = filter.change_freq(freq);
+        }
+        if let Some(min) = self.min_priority {
+            filter = filter.min_priority(min);
+        }
+        if let Some(max) = self.max_priority {
+            filter = filter.max_priority(max);
+        }
+        for &field in &self.has {
+            filter = filter.with_field(field.into());
+        }
+        for &field in &self.missing {
+            filter = filter.without_field(field.into());
+        }
+
+        Ok(filter)
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum FieldArg {
+    Lastmod,
+    Changefreq,
+    Priority,
+}
+
+impl From<FieldArg> for Field {
+    fn from(field: FieldArg) -> Self {
+        match field {
+            FieldArg::Lastmod => Field::LastMod,
+            FieldArg::Changefreq => Field::ChangeFreq,
+            FieldArg::Priority => Field::Priority,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Compare two versions of a sitemap or sitemap index
+    Diff {
+        /// Path or URL of the old version
+        old: String,
+        /// Path or URL of the new version
+        new: String,
+    },
+    /// Merge sitemaps into one, printed as XML, keeping a single entry per URL
+    Merge {
+        /// Paths or URLs of the sitemap.xml files
+        #[arg(required = true)]
+        paths: Vec<String>,
+        /// Which entry to keep when more than one lists the same URL
+        #[arg(long, value_enum, default_value_t = Policy::Newest)]
+        policy: Policy,
+        /// Write the result as sitemap files plus a sitemap index in this directory,
+        /// splitting it as needed to stay within 50,000 URLs and 50MB per file
+        #[arg(long, requires = "base_url")]
+        split: Option<PathBuf>,
+        /// The URL the split files will be published under
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// List the URLs in a sitemap that a robots.txt file disallows crawling
+    Robots {
+        /// Path or URL of the robots.txt file
+        robots: String,
+        /// Path or URL of the sitemap.xml file
+        #[arg(default_value = "-")]
+        sitemap: String,
+        /// The user agent whose rules apply
+        #[arg(short = 'A', long, default_value = "*")]
+        user_agent: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum Policy {
+    /// Keep the entry with the most recent <lastmod>
+    Newest,
+    /// Keep the entry with the highest <priority>
+    Priority,
+    /// Keep the entry read first
+    First,
+    /// Fail if more than one entry lists the same URL
+    Error,
+}
+
+impl From<Policy> for ConflictPolicy {
+    fn from(policy: Policy) -> Self {
+        match policy {
+            Policy::Newest => ConflictPolicy::NewestLastMod,
+            Policy::Priority => ConflictPolicy::HighestPriority,
+            Policy::First => ConflictPolicy::First,
+            Policy::Error => ConflictPolicy::Error,
+        }
+    }
 }
@@ -3,31 +3,170 @@ pub mod utils;
 
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
+use std::path::Path;
 
-use crate::cli::Cli;
-use crate::utils::build_output;
+use crate::cli::{Cli, Command};
+use crate::utils::{build_diff_output, build_output};
 use clap::Parser;
 
 use sitemaps::error::Error;
-use sitemaps::SitemapsFile;
+use sitemaps::fetch::{Fetcher, FileFetcher, HttpFetcher};
+use sitemaps::filter::Filter;
+use sitemaps::merge::merge;
+use sitemaps::robots::{discover, Robots, ROBOTS_TXT};
+use sitemaps::sitemap::Sitemap;
+use sitemaps::writer::{SitemapSetWriter, INDEX_FILE_NAME};
+use sitemaps::{Sitemaps, SitemapsFile, WriteOptions, MAX_ENTRIES};
 
 fn main() -> Result<(), Error> {
     let cli = Cli::parse();
 
-    if let Some(ref path) = cli.path {
-        let reader: Box<dyn BufRead> = match path.as_str() {
-            "-" => Box::new(BufReader::new(io::stdin())),
-            _ => Box::new(BufReader::new(File::open(path)?)),
+    if let Some(Command::Diff { ref old, ref new }) = cli.command {
+        let old = SitemapsFile::read(open(old)?)?;
+        let new = SitemapsFile::read(open(new)?)?;
+        let output = match (old, new) {
+            (SitemapsFile::Sitemap(old), SitemapsFile::Sitemap(new)) => {
+                build_diff_output(old.diff(&new), &cli)
+            }
+            (SitemapsFile::SiteIndex(old), SitemapsFile::SiteIndex(new)) => {
+                build_diff_output(old.diff(&new), &cli)
+            }
+            _ => {
+                println!("Cannot compare a sitemap with a sitemap index");
+                return Ok(());
+            }
         };
+        match output {
+            Ok(output) => println!("{}", output.trim_end()),
+            Err(err) => println!("{}", err),
+        }
+
+        return Ok(());
+    }
 
-        match SitemapsFile::read(reader) {
-            Ok(sitemap) => match build_output(sitemap, &cli) {
-                Ok(output) => println!("{}", output.trim_end()),
-                Err(err) => println!("{}", err.to_string()),
+    if let Some(Command::Merge {
+        ref paths,
+        policy,
+        ref split,
+        ref base_url,
+    }) = cli.command
+    {
+        let mut sitemaps = vec![];
+        for path in paths {
+            match SitemapsFile::read(open(path)?)? {
+                SitemapsFile::Sitemap(sitemap) => sitemaps.push(sitemap),
+                SitemapsFile::SiteIndex(_) => {
+                    println!("Cannot merge a sitemap index: {}", path);
+                    return Ok(());
+                }
+            }
+        }
+
+        match merge(sitemaps, policy.into()) {
+            Ok(sitemap) => match (split, base_url) {
+                (Some(dir), Some(base_url)) => write_split(&sitemap, dir, base_url)?,
+                _ if sitemap.entries.len() > MAX_ENTRIES => {
+                    println!(
+                        "{} Use --split to write a sitemap index.",
+                        Error::TooManyUrls
+                    )
+                }
+                _ => {
+                    sitemap.write_to(io::stdout(), WriteOptions::new())?;
+                    println!();
+                }
             },
-            Err(err) => println!("{}", err.to_string()),
+            Err(err) => println!("{}", err),
+        }
+
+        return Ok(());
+    }
+
+    if let Some(Command::Robots {
+        ref robots,
+        ref sitemap,
+        ref user_agent,
+    }) = cli.command
+    {
+        let robots = Robots::read(open(robots)?)?;
+        match SitemapsFile::read(open(sitemap)?) {
+            Ok(sitemap) => {
+                for issue in sitemap.validate_robots(&robots, user_agent).issues {
+                    if let Error::DisallowedByRobots(loc) = issue.error {
+                        println!("{}", loc);
+                    }
+                }
+            }
+            Err(err) => println!("{}", err),
+        }
+
+        return Ok(());
+    }
+
+    if let Some(ref path) = cli.path {
+        let filter = cli.filter.filter()?;
+        if path.ends_with(ROBOTS_TXT) {
+            let discovery = if is_url(path) {
+                discover(HttpFetcher::new(), path)
+            } else {
+                let path = Path::new(path);
+                let dir = path.parent().unwrap_or_else(|| Path::new(""));
+                discover(FileFetcher::new(dir), ROBOTS_TXT)
+            };
+
+            match discovery {
+                Ok(discovery) => {
+                    for sitemap in discovery.sitemaps {
+                        match sitemap.file {
+                            Ok(file) => print_output(file, &filter, &cli),
+                            Err(err) => println!("{}: {}", sitemap.location, err),
+                        }
+                    }
+                }
+                Err(err) => println!("{}", err),
+            }
+
+            return Ok(());
+        }
+
+        match SitemapsFile::read(open(path)?) {
+            Ok(sitemap) => print_output(sitemap, &filter, &cli),
+            Err(err) => println!("{}", err),
         }
     }
 
     Ok(())
 }
+
+/// Write a sitemap as a set of files plus an index, and print the index's path.
+fn write_split(sitemap: &Sitemap, dir: &Path, base_url: &str) -> Result<(), Error> {
+    let mut writer = SitemapSetWriter::new(dir, base_url)?;
+    for entry in &sitemap.entries {
+        writer.write_entry(entry)?;
+    }
+    writer.finish()?;
+
+    println!("{}", dir.join(INDEX_FILE_NAME).display());
+    Ok(())
+}
+
+/// Open standard input for `-`, a URL or a file.
+fn open(path: &str) -> Result<Box<dyn BufRead>, Error> {
+    match path {
+        "-" => Ok(Box::new(BufReader::new(io::stdin()))),
+        _ if is_url(path) => HttpFetcher::new().fetch(path),
+        _ => Ok(Box::new(BufReader::new(File::open(path)?))),
+    }
+}
+
+fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+fn print_output(mut sitemap: SitemapsFile, filter: &Filter, cli: &Cli) {
+    sitemap.retain(filter);
+    match build_output(sitemap, cli) {
+        Ok(output) => println!("{}", output.trim_end()),
+        Err(err) => println!("{}", err),
+    }
+}
@@ -2,12 +2,15 @@ use crate::cli::Cli;
 use comfy_table::presets::ASCII_MARKDOWN;
 use comfy_table::Table;
 use csv::Writer;
+use serde::Serialize;
 use serde_json;
+use sitemaps::diff::{Diff, DiffEntry};
 use sitemaps::{Entries, SitemapsFile};
 use std::{error::Error, io::Write};
 use tabwriter::TabWriter;
 
-const HEADERS: [&str; 4] = ["loc", "lastmod", "changefreq", "priority"];
+const HEADERS: [&str; 5] = ["loc", "lastmod", "changefreq", "priority", "images"];
+const DIFF_HEADERS: [&str; 5] = ["change", "loc", "field", "old", "new"];
 
 // TODO: move this serialization logic into the library and out of the cli
 // challenge will be removing the dependency on the Cli struct
@@ -19,18 +22,65 @@ pub(crate) fn build_output(sitemap: SitemapsFile, cli: &Cli) -> Result<String, s
     let (headers, columns) = build_headers_and_columns(&sitemap, cli);
     let rows = transpose_columns(columns);
 
+    Ok(render(headers, rows, cli))
+}
+
+/// Render a diff with a row per added or removed entry and per changed field.
+pub(crate) fn build_diff_output<T: DiffEntry + Serialize>(
+    diff: Diff<T>,
+    cli: &Cli,
+) -> Result<String, serde_json::Error> {
+    if cli.json {
+        return serde_json::to_string_pretty(&diff);
+    }
+
+    let mut rows = vec![];
+    for entry in &diff.added {
+        rows.push(vec![
+            String::from("added"),
+            entry.key().to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ]);
+    }
+    for entry in &diff.removed {
+        rows.push(vec![
+            String::from("removed"),
+            entry.key().to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ]);
+    }
+    for changed in &diff.changed {
+        for change in &changed.changes {
+            rows.push(vec![
+                String::from("changed"),
+                changed.new.key().to_string(),
+                change.field.to_string(),
+                change.old.clone().unwrap_or_default(),
+                change.new.clone().unwrap_or_default(),
+            ]);
+        }
+    }
+
+    Ok(render(DIFF_HEADERS.to_vec(), rows, cli))
+}
+
+fn render(headers: Vec<&str>, rows: Vec<Vec<String>>, cli: &Cli) -> String {
     if cli.csv {
-        return Ok(write_csv(headers, rows).unwrap());
+        return write_csv(headers, rows).unwrap();
     }
 
     if cli.markdown {
-        return Ok(markdown(headers, rows, cli.header));
+        return markdown(headers, rows, cli.header);
     }
 
     if cli.pretty {
-        Ok(pretty(headers, rows, cli.header))
+        pretty(headers, rows, cli.header)
     } else {
-        Ok(plain(headers, rows, cli.header))
+        plain(headers, rows, cli.header)
     }
 }
 
@@ -148,6 +198,16 @@ fn build_headers_and_columns(
 
             columns.push(priorities);
         }
+        if cli.images {
+            headers.push(HEADERS[4]);
+            let images = sitemap
+                .entries
+                .iter()
+                .map(|url| url.images.len().to_string())
+                .collect::<Vec<String>>();
+
+            columns.push(images);
+        }
     }
 
     (headers, columns)
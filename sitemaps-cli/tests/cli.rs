@@ -4,3 +4,281 @@ struct Test {
     input: &'static str,
     out: &'static str,
 }
+
+const PLAIN: [Test; 4] = [
+    Test {
+        input: "tests/inputs/example_1_url.xml",
+        out: "tests/expected/example_1_url.txt",
+    },
+    Test {
+        input: "tests/inputs/example_2_url.xml",
+        out: "tests/expected/example_2_url.txt",
+    },
+    Test {
+        input: "tests/inputs/sitemap.xml",
+        out: "tests/expected/sitemap.txt",
+    },
+    Test {
+        input: "tests/inputs/sitemap_index.xml",
+        out: "tests/expected/sitemap_index.txt",
+    },
+];
+
+const CSV: [Test; 4] = [
+    Test {
+        input: "tests/inputs/example_1_url.xml",
+        out: "tests/expected/example_1_url.csv",
+    },
+    Test {
+        input: "tests/inputs/example_2_url.xml",
+        out: "tests/expected/example_2_url.csv",
+    },
+    Test {
+        input: "tests/inputs/sitemap.xml",
+        out: "tests/expected/sitemap.csv",
+    },
+    Test {
+        input: "tests/inputs/sitemap_index.xml",
+        out: "tests/expected/sitemap_index.csv",
+    },
+];
+
+const JSON: [Test; 4] = [
+    Test {
+        input: "tests/inputs/example_1_url.xml",
+        out: "tests/expected/example_1_url.json",
+    },
+    Test {
+        input: "tests/inputs/example_2_url.xml",
+        out: "tests/expected/example_2_url.json",
+    },
+    Test {
+        input: "tests/inputs/sitemap.xml",
+        out: "tests/expected/sitemap.json",
+    },
+    Test {
+        input: "tests/inputs/sitemap_index.xml",
+        out: "tests/expected/sitemap_index.json",
+    },
+];
+
+fn run(test: &Test, args: &[&str]) {
+    let expected = std::fs::read_to_string(test.out).unwrap();
+
+    Command::cargo_bin("sitemaps")
+        .unwrap()
+        .args(args)
+        .arg(test.input)
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn test_plain() {
+    for test in &PLAIN {
+        run(test, &["-c", "-p"]);
+    }
+}
+
+#[test]
+fn test_csv() {
+    for test in &CSV {
+        run(test, &["-C", "-c", "-p"]);
+    }
+}
+
+#[test]
+fn test_json() {
+    for test in &JSON {
+        run(test, &["--json"]);
+    }
+}
+
+#[test]
+fn test_stdin() {
+    let test = &PLAIN[1];
+    let expected = std::fs::read_to_string(test.out).unwrap();
+
+    Command::cargo_bin("sitemaps")
+        .unwrap()
+        .args(["-c", "-p"])
+        .pipe_stdin(test.input)
+        .unwrap()
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn test_gzip() {
+    let test = Test {
+        input: "tests/inputs/example_2_url.xml.gz",
+        out: "tests/expected/example_2_url.txt",
+    };
+    run(&test, &["-c", "-p"]);
+}
+
+#[test]
+fn test_gzip_stdin() {
+    let expected = std::fs::read_to_string("tests/expected/example_2_url.txt").unwrap();
+
+    Command::cargo_bin("sitemaps")
+        .unwrap()
+        .args(["-c", "-p"])
+        .pipe_stdin("tests/inputs/example_2_url.xml.gz")
+        .unwrap()
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn test_text_sitemap() {
+    let test = Test {
+        input: "tests/inputs/text_sitemap.txt",
+        out: "tests/expected/text_sitemap.txt",
+    };
+    run(&test, &[]);
+}
+
+#[test]
+fn test_rss_feed() {
+    let test = Test {
+        input: "tests/inputs/rss.xml",
+        out: "tests/expected/rss.txt",
+    };
+    run(&test, &[]);
+}
+
+#[test]
+fn test_images() {
+    let test = Test {
+        input: "tests/inputs/image_sitemap.xml",
+        out: "tests/expected/image_sitemap.txt",
+    };
+    run(&test, &["-i"]);
+}
+
+#[test]
+fn test_robots_txt() {
+    let test = Test {
+        input: "tests/inputs/robots.txt",
+        out: "tests/expected/robots.txt",
+    };
+    run(&test, &["-c", "-p"]);
+}
+
+#[test]
+fn test_robots_disallow() {
+    let test = Test {
+        input: "tests/inputs/robots_sitemap.xml",
+        out: "tests/expected/robots_disallow.txt",
+    };
+    run(&test, &["robots", "tests/inputs/robots_disallow.txt"]);
+}
+
+#[test]
+fn test_robots_disallow_user_agent() {
+    let test = Test {
+        input: "tests/inputs/robots_sitemap.xml",
+        out: "tests/expected/robots_disallow_googlebot.txt",
+    };
+    run(
+        &test,
+        &[
+            "robots",
+            "-A",
+            "Googlebot",
+            "tests/inputs/robots_disallow.txt",
+        ],
+    );
+}
+
+#[test]
+fn test_diff() {
+    let test = Test {
+        input: "tests/inputs/diff_new.xml",
+        out: "tests/expected/diff.txt",
+    };
+    run(&test, &["diff", "tests/inputs/diff_old.xml"]);
+}
+
+#[test]
+fn test_diff_csv() {
+    let test = Test {
+        input: "tests/inputs/diff_new.xml",
+        out: "tests/expected/diff.csv",
+    };
+    run(&test, &["-C", "diff", "tests/inputs/diff_old.xml"]);
+}
+
+#[test]
+fn test_merge() {
+    let test = Test {
+        input: "tests/inputs/diff_new.xml",
+        out: "tests/expected/merge.xml",
+    };
+    run(&test, &["merge", "tests/inputs/diff_old.xml"]);
+}
+
+#[test]
+fn test_merge_split() {
+    let dir = std::env::temp_dir().join(format!("sitemaps-cli-merge-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    Command::cargo_bin("sitemaps")
+        .unwrap()
+        .args(["merge", "--split"])
+        .arg(&dir)
+        .args(["--base-url", "https://www.example.com/"])
+        .args(["tests/inputs/diff_old.xml", "tests/inputs/diff_new.xml"])
+        .assert()
+        .success();
+
+    let index = std::fs::read_to_string(dir.join("sitemap-index.xml")).unwrap();
+    assert!(index.contains("<loc>https://www.example.com/sitemap-1.xml</loc>"));
+    assert!(dir.join("sitemap-1.xml").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_filter_dates() {
+    let test = Test {
+        input: "tests/inputs/sitemap.xml",
+        out: "tests/expected/filter_dates.txt",
+    };
+    run(&test, &["--since", "2023-05-01", "--until", "2023-05-19"]);
+}
+
+#[test]
+fn test_filter_loc() {
+    let test = Test {
+        input: "tests/inputs/sitemap.xml",
+        out: "tests/expected/filter_loc.csv",
+    };
+    run(
+        &test,
+        &[
+            "-C",
+            "--glob",
+            "*pvtl1.xml",
+            "--regex",
+            "pvtl[13]",
+            "--host",
+            "www.govinfo.gov",
+            "--path-prefix",
+            "/bulkdata/",
+        ],
+    );
+}
+
+#[test]
+fn test_filter_no_match() {
+    let test = Test {
+        input: "tests/inputs/sitemap.xml",
+        out: "tests/expected/filter_none.txt",
+    };
+    run(&test, &["--freq", "monthly", "--missing", "changefreq"]);
+}
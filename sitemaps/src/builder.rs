@@ -0,0 +1,224 @@
+//! Build sitemap and sitemap index entries that are checked to be valid.
+//!
+//! ```rust
+//! use sitemaps::sitemap::UrlEntry;
+//!
+//! let entry = UrlEntry::builder()
+//!     .loc("https://www.example.com/")
+//!     .last_mod("2024-02-27")
+//!     .change_freq("daily")
+//!     .priority(0.8)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(entry.priority.unwrap().to_string(), "0.8");
+//! ```
+//!
+//! Each setter accepts typed values, such as a [`url::Url`], a chrono date or datetime
+//! or a [`ChangeFreq`], as well as strings to be parsed. A value that cannot be
+//! converted is reported by `build()` unless the element is set again with one that
+//! can. Only the first problem found is returned.
+
+use crate::error::Error;
+use crate::hreflang::Alternate;
+use crate::image::Image;
+use crate::news::News;
+use crate::siteindex::SitemapEntry;
+use crate::sitemap::{ChangeFreq, Priority, UrlEntry};
+use crate::validate_url;
+use crate::video::Video;
+use crate::w3c_datetime::W3CDateTime;
+
+/// A builder of [`UrlEntry`]s, created with [`UrlEntry::builder`].
+#[derive(Debug, Default)]
+pub struct UrlEntryBuilder {
+    entry: UrlEntry,
+    errors: Vec<Error>,
+}
+
+/// A builder of [`SitemapEntry`]s, created with [`SitemapEntry::builder`].
+#[derive(Debug, Default)]
+pub struct SitemapEntryBuilder {
+    entry: SitemapEntry,
+    errors: Vec<Error>,
+}
+
+/// Convert a value for an element, recording the problem if the conversion fails
+/// in place of any recorded for an earlier value of the element.
+fn convert<T, V>(errors: &mut Vec<Error>, element: &'static str, value: V) -> Option<T>
+where
+    V: TryInto<T>,
+    V::Error: Into<Error>,
+{
+    errors.retain(|error| {
+        !matches!(error, Error::InvalidElement { element: recorded, .. } if *recorded == element)
+    });
+    match value.try_into() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            errors.push(invalid(element, e.into()));
+            None
+        }
+    }
+}
+
+fn invalid(element: &'static str, source: Error) -> Error {
+    Error::InvalidElement {
+        element,
+        source: Box::new(source),
+    }
+}
+
+/// Check that a `<loc>` is present and valid, and return it normalized.
+fn build_loc(loc: &str) -> Result<String, Error> {
+    if loc.is_empty() {
+        return Err(Error::MissingLoc);
+    }
+
+    validate_url(loc).map_err(|e| invalid("loc", e))
+}
+
+impl UrlEntry {
+    /// Create a builder of a UrlEntry.
+    pub fn builder() -> UrlEntryBuilder {
+        UrlEntryBuilder::new()
+    }
+}
+
+impl UrlEntryBuilder {
+    /// Create a builder of an entry with no values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the URL of the page, from a [`url::Url`] or a string.
+    pub fn loc<L: AsRef<str>>(mut self, loc: L) -> Self {
+        self.entry.loc = loc.as_ref().to_string();
+        self
+    }
+
+    /// Set the date of last modification of the page, from a [`W3CDateTime`], a
+    /// chrono `NaiveDate` or `DateTime`, or a string in the W3C Datetime format.
+    pub fn last_mod<T>(mut self, last_mod: T) -> Self
+    where
+        T: TryInto<W3CDateTime>,
+        T::Error: Into<Error>,
+    {
+        self.entry.last_mod = convert(&mut self.errors, "lastmod", last_mod);
+        self
+    }
+
+    /// Set how frequently the page is likely to change, from a [`ChangeFreq`] or a
+    /// case-insensitive string such as `"daily"`.
+    pub fn change_freq<T>(mut self, change_freq: T) -> Self
+    where
+        T: TryInto<ChangeFreq>,
+        T::Error: Into<Error>,
+    {
+        self.entry.change_freq = convert(&mut self.errors, "changefreq", change_freq);
+        self
+    }
+
+    /// Set the priority of the page, from a [`Priority`], an `f32` or a decimal string.
+    pub fn priority<T>(mut self, priority: T) -> Self
+    where
+        T: TryInto<Priority>,
+        T::Error: Into<Error>,
+    {
+        self.entry.priority = convert(&mut self.errors, "priority", priority);
+        self
+    }
+
+    /// Add a localized version of the page.
+    pub fn alternate(mut self, alternate: Alternate) -> Self {
+        self.entry.alternates.push(alternate);
+        self
+    }
+
+    /// Add an image on the page.
+    pub fn image(mut self, image: Image) -> Self {
+        self.entry.images.push(image);
+        self
+    }
+
+    /// Add a video on the page.
+    pub fn video(mut self, video: Video) -> Self {
+        self.entry.videos.push(video);
+        self
+    }
+
+    /// Set the news article on the page. Its age is not checked, as that depends
+    /// on when the sitemap is published; see [`News::validate`].
+    pub fn news(mut self, news: News) -> Self {
+        self.entry.news = Some(news);
+        self
+    }
+
+    /// Build the entry, checking that its `<loc>` is present and a valid URL, which
+    /// is normalized, that each value given could be converted, and that its images,
+    /// videos and hreflang alternates are valid. The first problem found is returned.
+    pub fn build(self) -> Result<UrlEntry, Error> {
+        if let Some(error) = self.errors.into_iter().next() {
+            return Err(error);
+        }
+
+        let mut entry = self.entry;
+        entry.loc = build_loc(&entry.loc)?;
+        entry
+            .validate_images()
+            .map_err(|e| invalid("image:image", e))?;
+        entry
+            .validate_videos()
+            .map_err(|e| invalid("video:video", e))?;
+        for alternate in &entry.alternates {
+            alternate
+                .validate_hreflang()
+                .map_err(|e| invalid("xhtml:link", e))?;
+        }
+
+        Ok(entry)
+    }
+}
+
+impl SitemapEntry {
+    /// Create a builder of a SitemapEntry.
+    pub fn builder() -> SitemapEntryBuilder {
+        SitemapEntryBuilder::new()
+    }
+}
+
+impl SitemapEntryBuilder {
+    /// Create a builder of an entry with no values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the URL of the sitemap, from a [`url::Url`] or a string.
+    pub fn loc<L: AsRef<str>>(mut self, loc: L) -> Self {
+        self.entry.loc = loc.as_ref().to_string();
+        self
+    }
+
+    /// Set the date of last modification of the sitemap, from a [`W3CDateTime`], a
+    /// chrono `NaiveDate` or `DateTime`, or a string in the W3C Datetime format.
+    pub fn last_mod<T>(mut self, last_mod: T) -> Self
+    where
+        T: TryInto<W3CDateTime>,
+        T::Error: Into<Error>,
+    {
+        self.entry.last_mod = convert(&mut self.errors, "lastmod", last_mod);
+        self
+    }
+
+    /// Build the entry, checking that its `<loc>` is present and a valid URL, which
+    /// is normalized, and that each value given could be converted. The first problem
+    /// found is returned.
+    pub fn build(self) -> Result<SitemapEntry, Error> {
+        if let Some(error) = self.errors.into_iter().next() {
+            return Err(error);
+        }
+
+        let mut entry = self.entry;
+        entry.loc = build_loc(&entry.loc)?;
+        Ok(entry)
+    }
+}
@@ -0,0 +1,209 @@
+//! Compare two versions of a sitemap, sitemap index or resolved set of sitemaps.
+//!
+//! Entries are matched by their normalized `<loc>`, so that, for example,
+//! `https://www.example.com` and `https://www.example.com/` are the same entry.
+//!
+//! ```rust
+//! use sitemaps::sitemap::{Sitemap, UrlEntry};
+//! use sitemaps::Sitemaps;
+//!
+//! let old = Sitemap::new();
+//! let mut new = Sitemap::new();
+//! let mut entry = UrlEntry::new();
+//! entry.loc = String::from("https://www.example.com/");
+//! new.entries.push(entry);
+//!
+//! let diff = old.diff(&new);
+//! assert_eq!(diff.added.len(), 1);
+//! ```
+
+use core::fmt;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::normalize_loc as normalize;
+use crate::resolve::ResolvedEntry;
+use crate::siteindex::{SiteIndex, SitemapEntry};
+use crate::sitemap::{Sitemap, UrlEntry};
+
+/// The entries added to, removed from and changed between two versions of a document.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Diff<T> {
+    /// The entries only in the new version, in its order.
+    pub added: Vec<T>,
+    /// The entries only in the old version, in its order.
+    pub removed: Vec<T>,
+    /// The entries in both versions whose fields differ, in the new version's order.
+    pub changed: Vec<Changed<T>>,
+}
+
+/// An entry whose fields differ between two versions.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Changed<T> {
+    pub old: T,
+    pub new: T,
+    pub changes: Vec<FieldChange>,
+}
+
+/// A field whose value differs between two versions of an entry. The values are
+/// formatted as they are written to a sitemap, and `None` when the field is absent.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: Field,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// The fields compared between two versions of an entry.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub enum Field {
+    LastMod,
+    ChangeFreq,
+    Priority,
+}
+
+/// An entry that can be compared with another version of itself.
+pub trait DiffEntry {
+    /// The URL the entry is matched by.
+    fn key(&self) -> &str;
+    /// The fields whose values differ in `new`.
+    fn changes(&self, new: &Self) -> Vec<FieldChange>;
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match *self {
+            Field::LastMod => "lastmod",
+            Field::ChangeFreq => "changefreq",
+            Field::Priority => "priority",
+        };
+
+        f.write_str(data)
+    }
+}
+
+impl<T> Diff<T> {
+    /// Whether the two versions have the same entries, with the same values.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Record a change to a field if its old and new values differ.
+fn compare<V: PartialEq + ToString>(
+    changes: &mut Vec<FieldChange>,
+    field: Field,
+    old: &Option<V>,
+    new: &Option<V>,
+) {
+    if old != new {
+        changes.push(FieldChange {
+            field,
+            old: old.as_ref().map(ToString::to_string),
+            new: new.as_ref().map(ToString::to_string),
+        });
+    }
+}
+
+impl DiffEntry for UrlEntry {
+    fn key(&self) -> &str {
+        &self.loc
+    }
+
+    fn changes(&self, new: &Self) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        compare(&mut changes, Field::LastMod, &self.last_mod, &new.last_mod);
+        compare(
+            &mut changes,
+            Field::ChangeFreq,
+            &self.change_freq,
+            &new.change_freq,
+        );
+        compare(&mut changes, Field::Priority, &self.priority, &new.priority);
+        changes
+    }
+}
+
+impl DiffEntry for SitemapEntry {
+    fn key(&self) -> &str {
+        &self.loc
+    }
+
+    fn changes(&self, new: &Self) -> Vec<FieldChange> {
+        let mut changes = vec![];
+        compare(&mut changes, Field::LastMod, &self.last_mod, &new.last_mod);
+        changes
+    }
+}
+
+impl DiffEntry for ResolvedEntry {
+    fn key(&self) -> &str {
+        &self.entry.loc
+    }
+
+    fn changes(&self, new: &Self) -> Vec<FieldChange> {
+        self.entry.changes(&new.entry)
+    }
+}
+
+/// Compare two versions of a list of entries. Should a URL be listed more than once
+/// in a version, only its first entry is compared.
+pub fn diff<T: DiffEntry + Clone>(old: &[T], new: &[T]) -> Diff<T> {
+    let mut old_by_key = HashMap::new();
+    for entry in old {
+        old_by_key.entry(normalize(entry.key())).or_insert(entry);
+    }
+    let new_keys: HashSet<String> = new.iter().map(|entry| normalize(entry.key())).collect();
+
+    let mut result = Diff {
+        added: vec![],
+        removed: vec![],
+        changed: vec![],
+    };
+
+    let mut seen = HashSet::new();
+    for entry in new {
+        let key = normalize(entry.key());
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+
+        match old_by_key.get(&key) {
+            None => result.added.push(entry.clone()),
+            Some(old_entry) => {
+                let changes = old_entry.changes(entry);
+                if !changes.is_empty() {
+                    result.changed.push(Changed {
+                        old: (*old_entry).clone(),
+                        new: entry.clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+
+    seen.clear();
+    for entry in old {
+        let key = normalize(entry.key());
+        if seen.insert(key.clone()) && !new_keys.contains(&key) {
+            result.removed.push(entry.clone());
+        }
+    }
+
+    result
+}
+
+impl Sitemap {
+    /// Compare this sitemap with a newer version of it.
+    pub fn diff(&self, new: &Sitemap) -> Diff<UrlEntry> {
+        diff(&self.entries, &new.entries)
+    }
+}
+
+impl SiteIndex {
+    /// Compare this sitemap index with a newer version of it.
+    pub fn diff(&self, new: &SiteIndex) -> Diff<SitemapEntry> {
+        diff(&self.entries, &new.entries)
+    }
+}
@@ -1,8 +1,10 @@
+use crate::position::Position;
+use crate::w3c_datetime::W3CDateTime;
 use chrono::ParseError as ChronoParseError;
 use quick_xml::events::attributes::AttrError;
 use quick_xml::Error as XmlError;
+use std::convert::Infallible;
 use std::io::Error as IoError;
-use std::num::ParseFloatError;
 use thiserror::Error;
 use url::ParseError as UrlParseError;
 
@@ -18,9 +20,9 @@ pub enum Error {
     /// An error when reading or writing to a file or buffer.
     #[error("IO error")]
     IoError(#[from] IoError),
-    /// An error when creating a sitemap priority value.
-    #[error("Floating point parse error for Priority")]
-    ParsePriorityError(#[from] ParseFloatError),
+    /// An error when a sitemap priority value is not a decimal number.
+    #[error("Invalid priority: {0:?}")]
+    InvalidPriority(String),
     /// An error when an incorrect encoding declaration is encountered.
     #[error("Sitemap encoding error. Must be \"utf-8\"")]
     EncodingError,
@@ -31,9 +33,97 @@ pub enum Error {
     /// or more than 50,000 `<sitemap>` elements in a sitemap index file.
     #[error("Too many URLs in document. Cannot exceed 50,000.")]
     TooManyUrls,
+    /// An error when a sitemap file is larger than 50MB uncompressed.
+    #[error("Sitemap exceeds size limit: must not be more than 50MB uncompressed.")]
+    FileTooLarge,
     /// An error when a url in a `<loc>` element exceeds 2048 characters in length.
     #[error("URL exceeds length limit: must not be more than 2048 characters.")]
     UrlValueTooLong,
+    /// An error when more than 1,000 `<image:image>` elements are listed for a URL.
+    #[error("Too many images for URL. Cannot exceed 1,000.")]
+    TooManyImages,
+    /// An error when a required child of a `<video:video>` element is missing.
+    #[error("Video is missing required element <video:{0}>")]
+    MissingVideoField(&'static str),
+    /// An error when a `<video:video>` child element contains a value that cannot be parsed.
+    #[error("Invalid value for <video:{field}>: {value:?}")]
+    InvalidVideoValue { field: &'static str, value: String },
+    /// An error when a video duration is not between 1 and 28,800 seconds.
+    #[error("Video duration must be between 1 and 28800 seconds, got {0}")]
+    VideoDurationOutOfRange(u32),
+    /// An error when a video rating is not between 0.0 and 5.0.
+    #[error("Video rating must be between 0.0 and 5.0, got {0}")]
+    VideoRatingOutOfRange(f32),
+    /// An error when a required child of a `<news:news>` element is missing.
+    #[error("News article is missing required element <news:{0}>")]
+    MissingNewsField(&'static str),
+    /// An error when a news publication's language is not an ISO 639 code.
+    #[error("Invalid news publication language: {0:?}")]
+    InvalidNewsLanguage(String),
+    /// An error when there are more than 1,000 `<news:news>` elements in a sitemap.
+    #[error("Too many news URLs in document. Cannot exceed 1,000.")]
+    TooManyNewsUrls,
+    /// An error when a news article was published more than two days ago.
+    #[error("News article published {0} is older than two days")]
+    NewsArticleTooOld(W3CDateTime),
+    /// An error when an `<xhtml:link>` element has an invalid `hreflang` value.
+    #[error("Invalid hreflang: {0:?}")]
+    InvalidHreflang(String),
+    /// An error when a URL with hreflang alternates does not list itself among them.
+    #[error("Alternates do not include the URL itself")]
+    MissingSelfAlternate,
+    /// An error when an alternate URL does not link back to the URL that lists it.
+    #[error("Alternate {0} does not link back")]
+    MissingReturnLink(String),
+    /// An error when an alternate URL lists a different set of alternates than the
+    /// URL that lists it, so the two are not in the same cluster.
+    #[error("Alternate {0} lists different alternates")]
+    InconsistentAlternates(String),
+    /// An error when the same URL is listed in more than one entry.
+    #[error("Duplicate URL: {0}")]
+    DuplicateLoc(String),
+    /// An error when the root element's namespace is not the sitemaps.org one.
+    #[error("Non-standard namespace: {0:?}")]
+    NonStandardNamespace(String),
+    /// An error when a URL is outside the directory the sitemap is in.
+    #[error("URL out of the sitemap's scope: {0}")]
+    OutOfScope(String),
+    /// An error when a URL may not be crawled according to robots.txt.
+    #[error("URL disallowed by robots.txt: {0}")]
+    DisallowedByRobots(String),
+    /// An error when a sitemap index lists a sitemap that has already been loaded,
+    /// either more than once or in a cycle of nested indexes.
+    #[error("Sitemap already loaded: {0}")]
+    SitemapCycle(String),
+    /// An error when a sitemap index lists another sitemap index nested deeper than allowed.
+    #[error("Sitemap index nested too deeply: {0}")]
+    NestedSiteIndex(String),
+    /// An error when a location cannot be loaded by a fetcher.
+    #[error("Cannot fetch {0}")]
+    UnsupportedLocation(String),
+    /// An HTTP error when fetching a sitemap.
+    #[cfg(feature = "http")]
+    #[error("HTTP error")]
+    HttpError(#[from] Box<ureq::Error>),
+    /// An error when a filter's regular expression is invalid.
+    #[cfg(feature = "regex")]
+    #[error("Invalid regular expression")]
+    RegexError(#[from] regex::Error),
+    /// An error when an entry built with a builder has no `<loc>`.
+    #[error("Entry is missing required element <loc>")]
+    MissingLoc,
+    /// An error in the value given for an element of an entry built with a builder.
+    #[error("Invalid <{element}>: {source}")]
+    InvalidElement {
+        element: &'static str,
+        source: Box<Error>,
+    },
+    /// An error in a sitemap loaded from a sitemap index.
+    #[error("{source} in sitemap {sitemap}")]
+    InvalidSitemap { source: Box<Error>, sitemap: String },
+    /// An error in a particular entry of a sitemap or sitemap index.
+    #[error("{source} in entry {entry}")]
+    InvalidEntry { source: Box<Error>, entry: usize },
     /// An error when a priority value is less than 0.0.
     #[error("Priority must not be lower than 0.0")]
     PriorityTooLow,
@@ -46,7 +136,25 @@ pub enum Error {
     /// An error when EOF is encountered unexpectedly early.
     #[error("Unexpected EOF")]
     UnexpectedEof,
-    /// An error when a file that is not a sitemap or sitemap index is passed in for reading.
-    #[error("Not a sitemap: root element must be <urlset> or <sitemapindex>")]
+    /// An error when a file that is not a sitemap, sitemap index or feed is passed in for reading.
+    #[error("Not a sitemap: root element must be <urlset>, <sitemapindex>, <rss> or <feed>")]
     NotASitemap,
+    /// An error when a `<changefreq>` element contains an unrecognized value.
+    #[error("Invalid change frequency: {0:?}")]
+    InvalidChangeFreq(String),
+    /// An error encountered while reading a sitemap or sitemap index, along with
+    /// where in the input it occurred and the index of the entry being parsed.
+    #[error("{source} at {position}{}", .entry.map(|i| format!(", in entry {}", i)).unwrap_or_default())]
+    ParseError {
+        source: Box<Error>,
+        position: Position,
+        entry: Option<usize>,
+    },
+}
+
+/// Lets conversions that cannot fail be used wherever a fallible one is accepted.
+impl From<Infallible> for Error {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
 }
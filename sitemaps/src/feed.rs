@@ -0,0 +1,178 @@
+//! Read RSS 2.0 and Atom 0.3/1.0 feeds as sitemaps.
+//!
+//! Each RSS `<item>` or Atom `<entry>` becomes a [`UrlEntry`]. The `<loc>` is taken
+//! from an item's `<link>` (or its `<guid>` when it is a permalink) or from an entry's
+//! alternate `<link href="...">`. The `<lastmod>` is taken from an item's RFC 2822
+//! `<pubDate>` or from an entry's `<updated>` (Atom 1.0) or `<modified>` (Atom 0.3);
+//! empty dates are ignored. Items without a URL are skipped.
+
+use chrono::DateTime;
+use quick_xml::events::{BytesStart, Event};
+use std::io::BufRead;
+
+use crate::error::Error;
+use crate::sitemap::{Sitemap, UrlEntry};
+use crate::w3c_datetime::W3CDateTime;
+use crate::{read_root, with_position, xml_reader, Sitemaps, XmlReader};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Kind {
+    Rss,
+    Atom,
+}
+
+/// The parts of a feed item that map onto a sitemap entry.
+#[derive(Default)]
+struct Item {
+    link: Option<String>,
+    guid: Option<String>,
+    last_mod: Option<W3CDateTime>,
+}
+
+/// Read an RSS 2.0 or Atom feed into a [`Sitemap`].
+pub fn read_feed<R: BufRead>(reader: R) -> Result<Sitemap, Error> {
+    let mut reader = xml_reader(reader);
+    let root = read_root(&mut reader).map_err(|e| with_position(&reader, None, e))?;
+    from_root(reader, &root)
+}
+
+/// Whether a root element is that of a feed that can be read as a sitemap.
+pub(crate) fn is_feed(root: &BytesStart) -> bool {
+    kind(root).is_some()
+}
+
+fn kind(root: &BytesStart) -> Option<Kind> {
+    match root.local_name().as_ref() {
+        b"rss" => Some(Kind::Rss),
+        b"feed" => Some(Kind::Atom),
+        _ => None,
+    }
+}
+
+/// Read a feed from an XML reader positioned just after the `<rss>` or `<feed>` root element.
+pub(crate) fn from_root<R: BufRead>(
+    mut reader: XmlReader<R>,
+    root: &BytesStart,
+) -> Result<Sitemap, Error> {
+    let kind = kind(root).ok_or(Error::NotASitemap)?;
+    let mut sitemap = Sitemap::new();
+    let mut in_item = false;
+
+    read_items(&mut reader, kind, &mut sitemap, &mut in_item).map_err(|e| {
+        let entry = if in_item {
+            Some(sitemap.entries.len())
+        } else {
+            None
+        };
+        with_position(&reader, entry, e)
+    })?;
+
+    Ok(sitemap)
+}
+
+fn read_items<R: BufRead>(
+    reader: &mut XmlReader<R>,
+    kind: Kind,
+    sitemap: &mut Sitemap,
+    in_item: &mut bool,
+) -> Result<(), Error> {
+    let item_tag: &[u8] = match kind {
+        Kind::Rss => b"item",
+        Kind::Atom => b"entry",
+    };
+
+    let mut buf = Vec::new();
+    let mut item = Item::default();
+    let mut guid_is_permalink = true;
+    let mut text = String::new();
+
+    loop {
+        buf.clear();
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => return Ok(()),
+            Event::Start(start) => {
+                let name = start.local_name();
+                if name.as_ref() == item_tag {
+                    *in_item = true;
+                    item = Item::default();
+                } else if *in_item {
+                    text.clear();
+                    match (kind, name.as_ref()) {
+                        (Kind::Atom, b"link") if item.link.is_none() => {
+                            item.link = alternate_href(reader, &start)?;
+                        }
+                        (Kind::Rss, b"guid") => {
+                            guid_is_permalink = true;
+                            for attr_result in start.attributes() {
+                                let a = attr_result?;
+                                if a.key.as_ref() == b"isPermaLink" {
+                                    guid_is_permalink =
+                                        a.decode_and_unescape_value(reader)? != "false";
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Event::Text(e) if *in_item => text.push_str(&e.unescape()?),
+            Event::CData(e) if *in_item => text.push_str(&reader.decoder().decode(&e)?),
+            Event::End(end) if *in_item => {
+                let name = end.local_name();
+                let value = text.trim();
+                match (kind, name.as_ref()) {
+                    (_, tag) if tag == item_tag => {
+                        *in_item = false;
+                        let item = std::mem::take(&mut item);
+                        if let Some(loc) = item.link.or(item.guid) {
+                            let mut url = UrlEntry::new();
+                            url.loc = loc;
+                            url.last_mod = item.last_mod;
+                            sitemap.entries.push(url);
+                        }
+                    }
+                    (Kind::Rss, b"link") if !value.is_empty() => {
+                        item.link = Some(value.to_string());
+                    }
+                    (Kind::Rss, b"guid") if guid_is_permalink && !value.is_empty() => {
+                        item.guid = Some(value.to_string());
+                    }
+                    (Kind::Rss, b"pubDate") if !value.is_empty() => {
+                        let datetime = DateTime::parse_from_rfc2822(value)?;
+                        item.last_mod = Some(W3CDateTime::DateTime(datetime, 0, false));
+                    }
+                    (Kind::Atom, b"updated") if !value.is_empty() => {
+                        item.last_mod = Some(W3CDateTime::new(value)?);
+                    }
+                    (Kind::Atom, b"modified") if item.last_mod.is_none() && !value.is_empty() => {
+                        item.last_mod = Some(W3CDateTime::new(value)?);
+                    }
+                    _ => {}
+                }
+                text.clear();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Return the `href` of an Atom `<link>` element if it links to an alternate
+/// version of the entry, which is the default relation.
+fn alternate_href<R: BufRead>(
+    reader: &XmlReader<R>,
+    start: &BytesStart,
+) -> Result<Option<String>, Error> {
+    let mut href = None;
+    let mut alternate = true;
+
+    for attr_result in start.attributes() {
+        let a = attr_result?;
+        match a.key.as_ref() {
+            b"href" => href = Some(a.decode_and_unescape_value(reader)?.to_string()),
+            b"rel" => alternate = a.decode_and_unescape_value(reader)? == "alternate",
+            _ => {}
+        }
+    }
+
+    Ok(href.filter(|_| alternate))
+}
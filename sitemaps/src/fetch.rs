@@ -0,0 +1,102 @@
+//! Load sitemaps from the locations listed in sitemap indexes.
+//!
+//! A [`Fetcher`] turns a location, such as the `<loc>` of a sitemap index entry, into
+//! a reader of its contents. [`FileFetcher`] reads mirrored sitemaps from disk, and,
+//! with the `http` feature enabled, [`HttpFetcher`] downloads them.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+use crate::error::Error;
+
+/// A source of sitemap files.
+pub trait Fetcher {
+    /// Return a reader of the contents of the file at `location`.
+    fn fetch(&mut self, location: &str) -> Result<Box<dyn BufRead>, Error>;
+}
+
+impl<F: Fetcher + ?Sized> Fetcher for &mut F {
+    fn fetch(&mut self, location: &str) -> Result<Box<dyn BufRead>, Error> {
+        (**self).fetch(location)
+    }
+}
+
+/// Reads files from a directory. A URL is mapped to the file at its path under the
+/// directory, so that `https://www.example.com/sitemaps/sitemap.xml` is read from
+/// `<root>/sitemaps/sitemap.xml`, whatever the host. `file:` URLs and paths are
+/// read as is, with relative paths taken relative to the directory.
+#[derive(Debug, Clone)]
+pub struct FileFetcher {
+    root: PathBuf,
+}
+
+impl FileFetcher {
+    /// Create a fetcher that reads files from `root`.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The path of the file that a location is read from.
+    pub fn path(&self, location: &str) -> Result<PathBuf, Error> {
+        match Url::parse(location) {
+            Ok(url) if url.scheme() == "file" => url
+                .to_file_path()
+                .map_err(|_| Error::UnsupportedLocation(location.to_string())),
+            // A single letter scheme is a Windows drive letter rather than a URL.
+            Ok(url) if url.scheme().len() > 1 => {
+                Ok(self.root.join(url.path().trim_start_matches('/')))
+            }
+            _ => Ok(self.root.join(location)),
+        }
+    }
+}
+
+impl Fetcher for FileFetcher {
+    fn fetch(&mut self, location: &str) -> Result<Box<dyn BufRead>, Error> {
+        let file = File::open(self.path(location)?)?;
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Downloads files over HTTP(S).
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+pub struct HttpFetcher {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "http")]
+impl HttpFetcher {
+    /// Create a fetcher identifying itself with a `sitemaps` user agent.
+    pub fn new() -> Self {
+        Self::with_agent(
+            ureq::AgentBuilder::new()
+                .user_agent(concat!("sitemaps/", env!("CARGO_PKG_VERSION")))
+                .build(),
+        )
+    }
+
+    /// Create a fetcher that makes requests with a configured agent.
+    pub fn with_agent(agent: ureq::Agent) -> Self {
+        Self { agent }
+    }
+}
+
+#[cfg(feature = "http")]
+impl Default for HttpFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "http")]
+impl Fetcher for HttpFetcher {
+    fn fetch(&mut self, location: &str) -> Result<Box<dyn BufRead>, Error> {
+        let response = self.agent.get(location).call().map_err(Box::new)?;
+        Ok(Box::new(BufReader::new(response.into_reader())))
+    }
+}
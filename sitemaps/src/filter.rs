@@ -0,0 +1,296 @@
+//! Select the entries of a sitemap or sitemap index that match some criteria.
+//!
+//! ```rust
+//! use sitemaps::filter::Filter;
+//! use sitemaps::w3c_datetime::W3CDateTime;
+//!
+//! let filter = Filter::new()
+//!     .since(W3CDateTime::new("2024-01-01").unwrap())
+//!     .path_prefix("/blog/");
+//! ```
+//!
+//! All the criteria given must match for an entry to match. Criteria on fields an
+//! entry doesn't have, such as `<priority>` for sitemap index entries, never match.
+
+use url::Url;
+
+use crate::diff::Field;
+#[cfg(feature = "regex")]
+use crate::error::Error;
+use crate::siteindex::{SiteIndex, SitemapEntry};
+use crate::sitemap::{ChangeFreq, Priority, Sitemap, UrlEntry};
+use crate::w3c_datetime::W3CDateTime;
+use crate::SitemapsFile;
+
+/// Criteria to match sitemap and sitemap index entries against.
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    since: Option<W3CDateTime>,
+    until: Option<W3CDateTime>,
+    loc_globs: Vec<String>,
+    #[cfg(feature = "regex")]
+    loc_regex: Option<regex::Regex>,
+    host: Option<String>,
+    path_prefix: Option<String>,
+    change_freqs: Vec<ChangeFreq>,
+    min_priority: Option<Priority>,
+    max_priority: Option<Priority>,
+    present: Vec<Field>,
+    absent: Vec<Field>,
+}
+
+/// An entry that can be matched against a [`Filter`].
+pub trait FilterEntry {
+    fn loc(&self) -> &str;
+    fn last_mod(&self) -> Option<W3CDateTime>;
+    fn change_freq(&self) -> Option<ChangeFreq> {
+        None
+    }
+    fn priority(&self) -> Option<Priority> {
+        None
+    }
+}
+
+impl FilterEntry for UrlEntry {
+    fn loc(&self) -> &str {
+        &self.loc
+    }
+    fn last_mod(&self) -> Option<W3CDateTime> {
+        self.last_mod
+    }
+    fn change_freq(&self) -> Option<ChangeFreq> {
+        self.change_freq
+    }
+    fn priority(&self) -> Option<Priority> {
+        self.priority
+    }
+}
+
+impl FilterEntry for SitemapEntry {
+    fn loc(&self) -> &str {
+        &self.loc
+    }
+    fn last_mod(&self) -> Option<W3CDateTime> {
+        self.last_mod
+    }
+}
+
+impl Filter {
+    /// Create a filter that matches every entry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match entries last modified at or after `since`. A year, month or date means
+    /// the start of that period, UTC.
+    pub fn since(mut self, since: W3CDateTime) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Match entries last modified at or before `until`. A year, month or date means
+    /// the end of that period, UTC, so that entries modified during it match.
+    pub fn until(mut self, until: W3CDateTime) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Match entries whose `<loc>` matches a glob pattern, where `*` matches any
+    /// sequence of characters and `?` any one character. Entries matching any of
+    /// the patterns given match.
+    pub fn loc_glob<P: Into<String>>(mut self, pattern: P) -> Self {
+        self.loc_globs.push(pattern.into());
+        self
+    }
+
+    /// Match entries whose `<loc>` contains a match of a regular expression.
+    #[cfg(feature = "regex")]
+    pub fn loc_regex(mut self, pattern: &str) -> Result<Self, Error> {
+        self.loc_regex = Some(regex::Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Match entries whose `<loc>` is on a host, case insensitively.
+    pub fn host<H: Into<String>>(mut self, host: H) -> Self {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Match entries whose `<loc>` has a path starting with `prefix`.
+    pub fn path_prefix<P: Into<String>>(mut self, prefix: P) -> Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Match entries with a `<changefreq>` of `change_freq`, or of any of the
+    /// change frequencies given.
+    pub fn change_freq(mut self, change_freq: ChangeFreq) -> Self {
+        self.change_freqs.push(change_freq);
+        self
+    }
+
+    /// Match entries with a `<priority>` of at least `min`.
+    pub fn min_priority(mut self, min: Priority) -> Self {
+        self.min_priority = Some(min);
+        self
+    }
+
+    /// Match entries with a `<priority>` of at most `max`.
+    pub fn max_priority(mut self, max: Priority) -> Self {
+        self.max_priority = Some(max);
+        self
+    }
+
+    /// Match entries that have a value for `field`.
+    pub fn with_field(mut self, field: Field) -> Self {
+        self.present.push(field);
+        self
+    }
+
+    /// Match entries that have no value for `field`.
+    pub fn without_field(mut self, field: Field) -> Self {
+        self.absent.push(field);
+        self
+    }
+
+    /// Check whether an entry matches all of the filter's criteria.
+    pub fn matches<E: FilterEntry + ?Sized>(&self, entry: &E) -> bool {
+        self.matches_last_mod(entry.last_mod())
+            && self.matches_loc(entry.loc())
+            && (self.change_freqs.is_empty()
+                || entry
+                    .change_freq()
+                    .map_or(false, |c| self.change_freqs.contains(&c)))
+            && self.matches_priority(entry.priority())
+            && self.present.iter().all(|&field| has_field(entry, field))
+            && !self.absent.iter().any(|&field| has_field(entry, field))
+    }
+
+    fn matches_last_mod(&self, last_mod: Option<W3CDateTime>) -> bool {
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let last_mod = match last_mod {
+            Some(last_mod) => last_mod.to_utc(),
+            None => return false,
+        };
+
+        let after_since = self.since.map_or(true, |since| last_mod >= since.to_utc());
+        let before_until = self
+            .until
+            .map_or(true, |until| last_mod < until.period_end());
+
+        after_since && before_until
+    }
+
+    fn matches_loc(&self, loc: &str) -> bool {
+        if !self.loc_globs.is_empty() && !self.loc_globs.iter().any(|p| glob_match(p, loc)) {
+            return false;
+        }
+
+        #[cfg(feature = "regex")]
+        if let Some(ref regex) = self.loc_regex {
+            if !regex.is_match(loc) {
+                return false;
+            }
+        }
+
+        if self.host.is_none() && self.path_prefix.is_none() {
+            return true;
+        }
+        let url = match Url::parse(loc) {
+            Ok(url) => url,
+            Err(_) => return false,
+        };
+
+        let on_host = self.host.as_ref().map_or(true, |host| {
+            url.host_str()
+                .map_or(false, |h| h.eq_ignore_ascii_case(host))
+        });
+        let under_prefix = self
+            .path_prefix
+            .as_ref()
+            .map_or(true, |prefix| url.path().starts_with(prefix.as_str()));
+
+        on_host && under_prefix
+    }
+
+    fn matches_priority(&self, priority: Option<Priority>) -> bool {
+        if self.min_priority.is_none() && self.max_priority.is_none() {
+            return true;
+        }
+
+        match priority {
+            Some(priority) => {
+                self.min_priority.map_or(true, |min| priority >= min)
+                    && self.max_priority.map_or(true, |max| priority <= max)
+            }
+            None => false,
+        }
+    }
+}
+
+fn has_field<E: FilterEntry + ?Sized>(entry: &E, field: Field) -> bool {
+    match field {
+        Field::LastMod => entry.last_mod().is_some(),
+        Field::ChangeFreq => entry.change_freq().is_some(),
+        Field::Priority => entry.priority().is_some(),
+    }
+}
+
+/// Match a whole string against a glob pattern with `*` and `?` wildcards.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` seen, and of the text when it was seen.
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` match one more character and try again.
+                Some((star, start)) => {
+                    p = star + 1;
+                    t = start + 1;
+                    backtrack = Some((star, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl Sitemap {
+    /// Keep only the entries that match a filter.
+    pub fn retain(&mut self, filter: &Filter) {
+        self.entries.retain(|entry| filter.matches(entry));
+    }
+}
+
+impl SiteIndex {
+    /// Keep only the entries that match a filter.
+    pub fn retain(&mut self, filter: &Filter) {
+        self.entries.retain(|entry| filter.matches(entry));
+    }
+}
+
+impl SitemapsFile {
+    /// Keep only the entries that match a filter.
+    pub fn retain(&mut self, filter: &Filter) {
+        match self {
+            SitemapsFile::Sitemap(sitemap) => sitemap.retain(filter),
+            SitemapsFile::SiteIndex(index) => index.retain(filter),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Support for gzip-compressed sitemaps, such as `sitemap.xml.gz` files.
+
+use flate2::bufread::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{BufRead, BufReader, Write};
+
+/// The magic bytes at the start of every gzip stream.
+pub const MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Check whether input starting with `start` is a gzip stream. `start` must hold
+/// at least the first two bytes of the input, unless the input is shorter.
+pub fn is_gzip(start: &[u8]) -> bool {
+    start.starts_with(&MAGIC)
+}
+
+/// Wrap a reader so that the decompressed contents of a gzip stream are read from it.
+pub fn decoder<R: BufRead>(reader: R) -> BufReader<MultiGzDecoder<R>> {
+    BufReader::new(MultiGzDecoder::new(reader))
+}
+
+/// Wrap a writer so that everything written to it is gzip-compressed.
+/// Call `finish` on the result to write the gzip trailer.
+pub fn encoder<W: Write>(writer: W) -> GzEncoder<W> {
+    GzEncoder::new(writer, Compression::default())
+}
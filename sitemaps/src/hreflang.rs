@@ -0,0 +1,110 @@
+//! Localized versions of a page, listed as [hreflang alternates](https://developers.google.com/search/docs/specialty/international/localized-versions#sitemap)
+//! with `<xhtml:link rel="alternate" hreflang="..." href="..."/>` elements in a `<url>`.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{NsReader, Writer};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+use crate::error::Error;
+use crate::namespace_prefix;
+
+/// The XHTML namespace, bound to the `xhtml` prefix.
+pub const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// The hreflang value for the page to show when no other language matches.
+pub const X_DEFAULT: &str = "x-default";
+
+/// A localized version of the page described by a URL entry.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Alternate {
+    /// The language, and optionally the region or script, of the localized page.
+    pub hreflang: String,
+    /// The URL of the localized page.
+    pub href: String,
+}
+
+impl Alternate {
+    /// Create a new Alternate.
+    pub fn new<L: Into<String>, H: Into<String>>(hreflang: L, href: H) -> Self {
+        Self {
+            hreflang: hreflang.into(),
+            href: href.into(),
+        }
+    }
+
+    /// Validate that the hreflang is `x-default` or a language code, such as `de`,
+    /// optionally followed by a script and/or region, such as `zh-Hant` or `de-CH`.
+    pub fn validate_hreflang(&self) -> Result<(), Error> {
+        if self.hreflang.eq_ignore_ascii_case(X_DEFAULT) {
+            return Ok(());
+        }
+
+        let mut subtags = self.hreflang.split('-');
+        let language = subtags.next().unwrap_or_default();
+        let mut valid =
+            (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic());
+
+        let mut subtag = subtags.next();
+        if let Some(script) = subtag {
+            if script.len() == 4 && script.chars().all(|c| c.is_ascii_alphabetic()) {
+                subtag = subtags.next();
+            }
+        }
+        if let Some(region) = subtag {
+            valid &= (region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()))
+                || (region.len() == 3 && region.chars().all(|c| c.is_ascii_digit()));
+        }
+        valid &= subtags.next().is_none();
+
+        if !valid {
+            return Err(Error::InvalidHreflang(self.hreflang.clone()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Read the attributes of an `<xhtml:link>` element, returning an Alternate if it
+/// has `rel="alternate"` and both `hreflang` and `href` attributes.
+pub(crate) fn read_alternate<R: BufRead>(
+    reader: &NsReader<R>,
+    start: &BytesStart,
+) -> Result<Option<Alternate>, Error> {
+    let mut rel = None;
+    let mut hreflang = None;
+    let mut href = None;
+
+    for attr_result in start.attributes() {
+        let a = attr_result?;
+        let value = a.decode_and_unescape_value(reader)?.to_string();
+        match a.key.as_ref() {
+            b"rel" => rel = Some(value),
+            b"hreflang" => hreflang = Some(value),
+            b"href" => href = Some(value),
+            _ => {}
+        }
+    }
+
+    match (rel.as_deref(), hreflang, href) {
+        (Some("alternate"), Some(hreflang), Some(href)) => Ok(Some(Alternate { hreflang, href })),
+        _ => Ok(None),
+    }
+}
+
+/// Write an `<xhtml:link>` element of the document whose root start tag is `root`,
+/// with the prefix `root` binds to the XHTML namespace.
+pub(crate) fn write_alternate<W: Write>(
+    writer: &mut Writer<W>,
+    root: &BytesStart,
+    alternate: &Alternate,
+) -> Result<(), Error> {
+    let prefix = namespace_prefix(root, XHTML_NAMESPACE, "xhtml");
+    let mut element = BytesStart::new(format!("{}:link", prefix));
+    element.push_attribute(("rel", "alternate"));
+    element.push_attribute(("hreflang", alternate.hreflang.as_str()));
+    element.push_attribute(("href", alternate.href.as_str()));
+    writer.write_event(Event::Empty(element))?;
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! The [Google image sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/image-sitemaps),
+//! which adds `<image:image>` elements to a `<url>`.
+
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Writer;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+use crate::error::Error;
+use crate::sitemap::Sitemap;
+use crate::{
+    namespace_prefix, read_fragment, read_text_content, resolve_element, validate_url,
+    write_fragment, Extension, Sitemaps, XmlReader,
+};
+
+/// The namespace of the image sitemap extension, bound to the `image` prefix.
+pub const IMAGE_NAMESPACE: &str = "http://www.google.com/schemas/sitemap-image/1.1";
+
+/// The maximum number of images that may be listed for a single URL.
+pub const MAX_IMAGES: usize = 1_000;
+
+/// The local names of the children of `<image:image>` that are read into an [`Image`].
+const ELEMENTS: &[&[u8]] = &[b"loc", b"caption", b"geo_location", b"title", b"license"];
+
+/// An image on the page described by a URL entry. It is a parent XML tag containing
+/// the required `<image:loc>` element. The other child elements have been deprecated
+/// by Google but are still read and written.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Image {
+    /// The URL of the image. It is required.
+    pub loc: String,
+    /// Optional. A caption for the image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    /// Optional. The geographic location of the image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo_location: Option<String>,
+    /// Optional. The title of the image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Optional. A URL to the license of the image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Optional. Any other child elements of the `<image:image>`, as XML fragments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<Extension>,
+}
+
+impl Image {
+    /// Create a new, empty Image.
+    pub fn new() -> Self {
+        Self {
+            loc: String::new(),
+            caption: None,
+            geo_location: None,
+            title: None,
+            license: None,
+            extensions: vec![],
+        }
+    }
+
+    /// Validate the URL contained in the image's `<image:loc>` element.
+    pub fn validate_loc(&self) -> Result<String, Error> {
+        validate_url(&self.loc)
+    }
+}
+
+/// Read the children of an `<image:image>` element, up to and including its end tag.
+pub(crate) fn read_image<R: BufRead>(reader: &mut XmlReader<R>) -> Result<Image, Error> {
+    let mut image = Image::new();
+    let mut buf = Vec::new();
+    let mut text_buf = Vec::new();
+
+    loop {
+        buf.clear();
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => return Err(Error::UnexpectedEof),
+            Event::Start(start) => {
+                let (namespace, local_name) =
+                    resolve_element(reader, start.name(), &[IMAGE_NAMESPACE]);
+                if namespace.is_none() || !ELEMENTS.contains(&local_name.as_ref()) {
+                    image.extensions.push(read_fragment(reader, &start)?);
+                    continue;
+                }
+
+                let text = read_text_content(reader, &mut text_buf)?;
+                match (namespace, local_name.as_ref()) {
+                    (Some(_), b"loc") => image.loc.push_str(&text),
+                    (Some(_), b"caption") => image.caption = Some(text),
+                    (Some(_), b"geo_location") => image.geo_location = Some(text),
+                    (Some(_), b"title") => image.title = Some(text),
+                    (Some(_), b"license") => image.license = Some(text),
+                    _ => {}
+                }
+            }
+            Event::End(end)
+                if matches!(
+                    resolve_element(reader, end.name(), &[IMAGE_NAMESPACE]),
+                    (Some(_), name) if name.as_ref() == b"image"
+                ) =>
+            {
+                return Ok(image)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Write an `<image:image>` element of the document whose root start tag is `root`,
+/// with the prefix `root` binds to the image namespace.
+pub(crate) fn write_image<W: Write>(
+    writer: &mut Writer<W>,
+    root: &BytesStart,
+    image: &Image,
+) -> Result<(), Error> {
+    let prefix = namespace_prefix(root, IMAGE_NAMESPACE, "image");
+    let name = format!("{}:image", prefix);
+    writer.write_event(Event::Start(BytesStart::new(name.as_str())))?;
+
+    Sitemap::write_text_element(writer, format!("{}:loc", prefix), &image.loc)?;
+
+    if let Some(ref caption) = image.caption {
+        Sitemap::write_text_element(writer, format!("{}:caption", prefix), caption)?;
+    }
+    if let Some(ref geo_location) = image.geo_location {
+        Sitemap::write_text_element(writer, format!("{}:geo_location", prefix), geo_location)?;
+    }
+    if let Some(ref title) = image.title {
+        Sitemap::write_text_element(writer, format!("{}:title", prefix), title)?;
+    }
+    if let Some(ref license) = image.license {
+        Sitemap::write_text_element(writer, format!("{}:license", prefix), license)?;
+    }
+    for extension in &image.extensions {
+        write_fragment(writer, root, extension)?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
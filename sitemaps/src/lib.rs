@@ -10,33 +10,61 @@
 //! let sitemap = SitemapsFile::read(reader).unwrap();
 //! ```
 
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, Chain, Cursor, Read};
 
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
-use serde::Serialize;
-use siteindex::SiteIndex;
-use sitemap::Sitemap;
+use serde::{Deserialize, Serialize};
+use siteindex::{SiteIndex, SiteIndexReader};
+use sitemap::{Sitemap, SitemapReader};
 
 use crate::error::Error;
-use quick_xml::{Reader, Writer};
+use crate::position::LineTracker;
+use quick_xml::escape::escape;
+use quick_xml::name::{LocalName, Namespace, PrefixDeclaration, QName, ResolveResult};
+use quick_xml::{NsReader, Writer};
 use std::borrow::Cow;
 use std::io::Write;
 use url::Url;
 
+pub mod builder;
+pub mod diff;
 pub mod error;
+pub mod feed;
+pub mod fetch;
+pub mod filter;
+#[cfg(feature = "gzip")]
+pub mod gzip;
+pub mod hreflang;
+pub mod image;
+pub mod merge;
+pub mod news;
+pub mod position;
+pub mod resolve;
+pub mod robots;
 pub mod siteindex;
 pub mod sitemap;
+pub mod validation;
+pub mod video;
 pub mod w3c_datetime;
+pub mod writer;
+
+/// The UTF-8 byte order mark.
+pub(crate) const BOM: &[u8] = b"\xEF\xBB\xBF";
 
 pub const NAMESPACE: &str = "http://www.sitemaps.org/schemas/sitemap/0.9";
 pub const MAX_URL_LENGTH: usize = 2048;
+/// The maximum number of entries in a sitemap or sitemap index file.
+pub const MAX_ENTRIES: usize = 50_000;
+/// The maximum size in bytes of an uncompressed sitemap or sitemap index file.
+pub const MAX_FILE_SIZE: usize = 52_428_800;
 
 /// A type representing the data in a sitemap file.
 ///
 /// A SitemapsFile is one of:
-/// - a Sitemap, representing sitemap.xml files with `<urlset>` as the root element
+/// - a Sitemap, representing sitemap.xml files with `<urlset>` as the root element,
+///   text sitemaps, and RSS or Atom feeds
 /// - a SiteIndex, representing sitemap.xml files with `<sitemapindex>` as the root element
-#[derive(Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum SitemapsFile {
     Sitemap(Sitemap),
     SiteIndex(SiteIndex),
@@ -44,39 +72,405 @@ pub enum SitemapsFile {
 
 impl SitemapsFile {
     /// Reads a buffer and returns a Sitemap or SiteIndex wrapped by the
-    /// Sitemaps enum wrapper.
-    pub fn read<R: BufRead>(mut reader: R) -> Result<Self, Error> {
-        let mut buf = String::new();
-        reader.read_to_string(&mut buf)?;
-        let mut xml_reader = Reader::from_str(&buf);
-        xml_reader.trim_text(true).expand_empty_elements(true);
-
-        loop {
-            match xml_reader.read_event() {
-                Err(e) => panic!(
-                    "Error at position {}: {:?}",
-                    xml_reader.buffer_position(),
-                    e
-                ),
-                Ok(Event::Eof) => return Err(Error::UnexpectedEof),
-                Ok(Event::Start(start)) => {
-                    let buf_reader = BufReader::new(buf.as_bytes());
-                    match start.name().as_ref() {
-                        b"urlset" => {
-                            let sitemap = Sitemap::read_from(buf_reader)?;
-                            return Ok(Self::Sitemap(sitemap));
-                        }
-                        b"sitemapindex" => {
-                            let siteindex = SiteIndex::read_from(buf_reader)?;
-                            return Ok(Self::SiteIndex(siteindex));
-                        }
-                        _ => return Err(Error::NotASitemap),
-                    }
-                }
-                _ => {}
+    /// Sitemaps enum wrapper. With the `gzip` feature enabled, gzip-compressed
+    /// input is detected and decompressed transparently.
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, Error> {
+        match Document::open(reader)? {
+            Document::Sitemap(reader) => Ok(Self::Sitemap(reader.into_sitemap()?)),
+            Document::SiteIndex(reader) => Ok(Self::SiteIndex(reader.into_site_index()?)),
+            Document::Whole(sitemap) => Ok(Self::Sitemap(sitemap)),
+        }
+    }
+}
+
+/// A document opened for reading. Sitemaps and sitemap indexes are read one entry at
+/// a time as their readers are advanced, while text sitemaps and feeds are read whole.
+pub(crate) enum Document<'a> {
+    Sitemap(SitemapReader<Box<dyn BufRead + 'a>>),
+    SiteIndex(SiteIndexReader<Box<dyn BufRead + 'a>>),
+    Whole(Sitemap),
+}
+
+impl<'a> Document<'a> {
+    /// Open a document, detecting and decompressing gzip-compressed input.
+    #[cfg(feature = "gzip")]
+    pub(crate) fn open<R: BufRead + 'a>(reader: R) -> Result<Self, Error> {
+        let (start, reader) = peek(reader, |start| start.len() >= gzip::MAGIC.len())?;
+        if gzip::is_gzip(&start) {
+            return Self::open_document(Box::new(gzip::decoder(reader)));
+        }
+
+        Self::open_document(Box::new(reader))
+    }
+
+    /// Open a document.
+    #[cfg(not(feature = "gzip"))]
+    pub(crate) fn open<R: BufRead + 'a>(reader: R) -> Result<Self, Error> {
+        Self::open_document(Box::new(reader))
+    }
+
+    /// Open either an XML document or, if the input does not start with markup,
+    /// read a [text sitemap](Sitemap::read_text).
+    fn open_document(reader: Box<dyn BufRead + 'a>) -> Result<Self, Error> {
+        let (start, reader) = peek(reader, |start| {
+            start.len() > MAX_FILE_SIZE || starts_with_markup(start).is_some()
+        })?;
+
+        if starts_with_markup(&start).unwrap_or(true) {
+            Self::open_xml(Box::new(reader))
+        } else {
+            Ok(Self::Whole(Sitemap::read_text(reader)?))
+        }
+    }
+
+    fn open_xml(reader: Box<dyn BufRead + 'a>) -> Result<Self, Error> {
+        let mut xml_reader = xml_reader(reader);
+        let root = read_root(&mut xml_reader).map_err(|e| with_position(&xml_reader, None, e))?;
+        match root.name().as_ref() {
+            b"urlset" => Ok(Self::Sitemap(SitemapReader::from_root(xml_reader, &root)?)),
+            b"sitemapindex" => Ok(Self::SiteIndex(SiteIndexReader::from_root(
+                xml_reader, &root,
+            )?)),
+            _ if feed::is_feed(&root) => Ok(Self::Whole(feed::from_root(xml_reader, &root)?)),
+            _ => Err(Error::NotASitemap),
+        }
+    }
+}
+
+/// A reader of the whole input, after its start has been read by [`peek`].
+pub(crate) type Peeked<R> = Chain<Cursor<Vec<u8>>, R>;
+
+/// Read the start of the input until `enough` holds for it or the input ends, and
+/// return it along with a reader of the whole input, the start included. Short
+/// reads, such as from pipes, are read past rather than taken as the whole start.
+pub(crate) fn peek<R: BufRead, F: Fn(&[u8]) -> bool>(
+    mut reader: R,
+    enough: F,
+) -> Result<(Vec<u8>, Peeked<R>), Error> {
+    let mut start = Vec::new();
+
+    while !enough(&start) {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        start.extend_from_slice(buf);
+        let len = buf.len();
+        reader.consume(len);
+    }
+
+    Ok((start.clone(), Cursor::new(start).chain(reader)))
+}
+
+/// Whether input starting with `start` is markup, its first character after any
+/// BOM and whitespace being `<`, or `None` if `start` is too short to tell.
+fn starts_with_markup(start: &[u8]) -> Option<bool> {
+    if BOM.starts_with(start) {
+        return None;
+    }
+
+    let start = start.strip_prefix(BOM).unwrap_or(start);
+    start
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .map(|b| *b == b'<')
+}
+
+/// The XML reader sitemaps and feeds are read with, which resolves the namespaces of
+/// elements and keeps track of where in the input it is.
+pub(crate) type XmlReader<R> = NsReader<LineTracker<R>>;
+
+/// Create an XML reader that skips whitespace between elements and reports empty
+/// elements as a start and an end tag.
+pub(crate) fn xml_reader<R: BufRead>(reader: R) -> XmlReader<R> {
+    let mut reader = NsReader::from_reader(LineTracker::new(reader));
+    reader.trim_text(true).expand_empty_elements(true);
+    reader
+}
+
+/// Resolve the name of an element that was just read to its namespace, if it is
+/// one of `namespaces`, and its local name. Extension elements are matched this
+/// way so that they are recognized whatever prefix their namespace is bound to.
+pub(crate) fn resolve_element<'n, R>(
+    reader: &NsReader<R>,
+    name: QName<'n>,
+    namespaces: &[&'static str],
+) -> (Option<&'static str>, LocalName<'n>) {
+    let (result, local_name) = reader.resolve_element(name);
+    let namespace = match result {
+        ResolveResult::Bound(Namespace(namespace)) => namespaces
+            .iter()
+            .find(|known| known.as_bytes() == namespace)
+            .copied(),
+        _ => None,
+    };
+
+    (namespace, local_name)
+}
+
+/// Advance an XML reader to the document's root element, checking the encoding
+/// declaration along the way, and return the root's start tag.
+pub(crate) fn read_root<R: BufRead>(
+    reader: &mut NsReader<R>,
+) -> Result<BytesStart<'static>, Error> {
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => return Err(Error::UnexpectedEof),
+            Event::Decl(e) => check_encoding(e)?,
+            Event::Start(start) => return Ok(start.into_owned()),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// Attach the reader's current position and the index of the entry being parsed, if
+/// any, to an error.
+pub(crate) fn with_position<R: BufRead>(
+    reader: &XmlReader<R>,
+    entry: Option<usize>,
+    error: Error,
+) -> Error {
+    if let Error::ParseError { .. } = error {
+        return error;
+    }
+
+    Error::ParseError {
+        source: Box::new(error),
+        position: reader.get_ref().position(reader.buffer_position()),
+        entry,
+    }
+}
+
+/// Read the text content of the element whose start tag was just read, up to and
+/// including the next tag.
+pub(crate) fn read_text_content<R: BufRead>(
+    reader: &mut NsReader<R>,
+    buf: &mut Vec<u8>,
+) -> Result<String, Error> {
+    let mut text = String::new();
+
+    loop {
+        buf.clear();
+        match reader.read_event_into(buf)? {
+            Event::Text(e) => text.push_str(&e.unescape()?),
+            Event::CData(e) => text.push_str(&reader.decoder().decode(&e)?),
+            _ => return Ok(text),
+        }
+    }
+}
+
+/// An element that isn't otherwise modelled, such as one from an unsupported
+/// extension, kept as an XML fragment.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Extension {
+    /// The element exactly as it was read, from its start tag to its end tag.
+    pub xml: String,
+    /// The declarations, such as `("xmlns:pagemap", "...")`, of the namespaces the
+    /// element uses that were declared outside of it. They are added to the
+    /// element's start tag when it is written to a document that doesn't declare them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub namespaces: Vec<(String, String)>,
+}
+
+/// Read an element whose start tag was just read, along with all of its content,
+/// up to and including its end tag, and return it as an XML fragment.
+pub(crate) fn read_fragment<R: BufRead>(
+    reader: &mut XmlReader<R>,
+    start: &BytesStart,
+) -> Result<Extension, Error> {
+    let mut namespaces = Vec::new();
+    let mut declared = Vec::new();
+    add_namespaces(reader, start, &mut declared, &mut namespaces)?;
+    reader.get_mut().start_capture();
+
+    let mut buf = Vec::new();
+    while !declared.is_empty() {
+        buf.clear();
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => return Err(Error::UnexpectedEof),
+            Event::Start(start) => add_namespaces(reader, &start, &mut declared, &mut namespaces)?,
+            Event::End(_) => {
+                declared.pop();
+            }
+            _ => {}
+        }
+    }
+
+    // Nothing but the end tag is left to read after an empty element, and as that
+    // is made up by the reader, no bytes are consumed for it.
+    let content = reader.get_mut().take_capture();
+    let mut xml = Vec::with_capacity(start.len() + content.len() + 3);
+    xml.push(b'<');
+    xml.extend_from_slice(start);
+    if content.is_empty() {
+        xml.extend_from_slice(b"/>");
+    } else {
+        xml.push(b'>');
+        xml.extend_from_slice(&content);
+    }
+
+    Ok(Extension {
+        xml: String::from_utf8_lossy(&xml).into_owned(),
+        namespaces,
+    })
+}
+
+/// Record the declarations of the namespaces used by the name or attributes of an
+/// element within a fragment, unless the fragment declares them itself. `declared`
+/// holds the prefixes declared by each of the element's ancestors in the fragment,
+/// and the element's own are pushed onto it.
+fn add_namespaces<R>(
+    reader: &NsReader<R>,
+    start: &BytesStart,
+    declared: &mut Vec<Vec<Vec<u8>>>,
+    namespaces: &mut Vec<(String, String)>,
+) -> Result<(), Error> {
+    let mut prefixes = Vec::new();
+    let mut used = vec![(start.name(), true)];
+    for attr_result in start.attributes() {
+        let key = attr_result?.key;
+        match key.as_namespace_binding() {
+            Some(PrefixDeclaration::Default) => prefixes.push(Vec::new()),
+            Some(PrefixDeclaration::Named(prefix)) => prefixes.push(prefix.to_vec()),
+            None if key.prefix().is_some() => used.push((key, false)),
+            None => {}
+        }
+    }
+    declared.push(prefixes);
+
+    for (name, is_element) in used {
+        let prefix = name.prefix().map_or(&b""[..], |prefix| prefix.into_inner());
+        if prefix == b"xml" || declared.iter().flatten().any(|p| p == prefix) {
+            continue;
+        }
+
+        let (result, _) = if is_element {
+            reader.resolve_element(name)
+        } else {
+            reader.resolve_attribute(name)
+        };
+        if let ResolveResult::Bound(Namespace(namespace)) = result {
+            let key = if prefix.is_empty() {
+                String::from("xmlns")
+            } else {
+                format!("xmlns:{}", String::from_utf8_lossy(prefix))
+            };
+            if !namespaces.iter().any(|(k, _)| *k == key) {
+                let namespace = String::from_utf8_lossy(namespace).into_owned();
+                namespaces.push((key, namespace));
             }
         }
     }
+
+    Ok(())
+}
+
+/// Write an XML fragment as it was read, adding declarations of the namespaces it
+/// uses that `root`, the start tag of the document's root element, doesn't make.
+pub(crate) fn write_fragment<W: Write>(
+    writer: &mut Writer<W>,
+    root: &BytesStart,
+    extension: &Extension,
+) -> Result<(), Error> {
+    let mut declarations = String::new();
+    for (key, namespace) in &extension.namespaces {
+        let declared = match root.try_get_attribute(key.as_str())? {
+            Some(attr) => attr.unescape_value()? == namespace.as_str(),
+            None => false,
+        };
+        if !declared {
+            declarations.push_str(&format!(" {}=\"{}\"", key, escape(namespace)));
+        }
+    }
+
+    let xml = &extension.xml;
+    let name_end = xml
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(xml.len());
+    let fragment = format!("{}{}{}", &xml[..name_end], declarations, &xml[name_end..]);
+    writer.write_event(Event::Text(BytesText::from_escaped(fragment)))?;
+    Ok(())
+}
+
+/// The prefix that `root`, the start tag of the document's root element, binds to
+/// `namespace`, or `default` if it binds none.
+pub(crate) fn namespace_prefix(root: &BytesStart, namespace: &str, default: &str) -> String {
+    root.attributes()
+        .flatten()
+        .find_map(|attr| match attr.key.as_namespace_binding() {
+            Some(PrefixDeclaration::Named(prefix))
+                if attr
+                    .unescape_value()
+                    .map_or(false, |value| value == namespace) =>
+            {
+                Some(String::from_utf8_lossy(prefix).into_owned())
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| default.to_string())
+}
+
+/// Validate a URL such as the value of a `<loc>` element, returning it in normalized form.
+pub(crate) fn validate_url(loc: &str) -> Result<String, Error> {
+    if loc.chars().count() > MAX_URL_LENGTH {
+        return Err(Error::UrlValueTooLong);
+    }
+
+    let url = Url::parse(loc)?;
+
+    Ok(url.as_str().into())
+}
+
+/// Normalize a `<loc>` for matching entries, leaving invalid URLs as they are.
+pub(crate) fn normalize_loc(loc: &str) -> String {
+    validate_url(loc).unwrap_or_else(|_| loc.to_string())
+}
+
+/// Check that an XML declaration specifies UTF-8 encoding.
+pub(crate) fn check_encoding(e: BytesDecl) -> Result<(), Error> {
+    let encoding = e.encoding();
+
+    if encoding.is_none() {
+        return Err(Error::EncodingError);
+    }
+
+    if let Some(Ok(Cow::Borrowed(encoding))) = encoding {
+        if !encoding.eq_ignore_ascii_case(b"utf-8") {
+            return Err(Error::EncodingError);
+        }
+    }
+    Ok(())
+}
+
+/// Options for writing a sitemap or sitemap index with [`Sitemaps::write_to`].
+///
+/// ```rust
+/// use sitemaps::sitemap::Sitemap;
+/// use sitemaps::{Sitemaps, WriteOptions};
+///
+/// let xml = Sitemap::new().write_to(Vec::new(), WriteOptions::new()).unwrap();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    #[cfg(feature = "gzip")]
+    gzip: bool,
+}
+
+impl WriteOptions {
+    /// Create options for writing uncompressed XML.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compress the output with gzip, for a `.xml.gz` file.
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self
+    }
 }
 
 /// A trait containing the behavior [`Sitemap`s](Sitemap) and [`SiteIndex`es](SiteIndex).
@@ -88,23 +482,18 @@ pub trait Sitemaps {
 
     /// Check that the encoding of the file being read from is UTF-8.
     fn check_encoding(e: BytesDecl) -> Result<(), Error> {
-        let encoding = e.encoding();
-
-        if encoding.is_none() {
-            return Err(Error::EncodingError);
-        }
-
-        if let Some(Ok(Cow::Borrowed(encoding))) = encoding {
-            if encoding.to_ascii_lowercase() != b"utf-8" {
-                return Err(Error::EncodingError);
-            }
-        }
-        Ok(())
+        check_encoding(e)
     }
     fn write<W: Write>(&self, writer: Writer<W>) -> Result<W, Error>;
 
-    /// Serialize a Sitemap to a Writer as XML.
-    fn write_to<W: Write>(&self, writer: W) -> Result<W, Error> {
+    /// Serialize a Sitemap to a Writer as XML, compressed if the options say so.
+    #[cfg_attr(not(feature = "gzip"), allow(unused_variables))]
+    fn write_to<W: Write>(&self, writer: W, options: WriteOptions) -> Result<W, Error> {
+        #[cfg(feature = "gzip")]
+        if options.gzip {
+            return Ok(self.write(Writer::new(gzip::encoder(writer)))?.finish()?);
+        }
+
         self.write(Writer::new(writer))
     }
 
@@ -136,13 +525,7 @@ pub trait SitemapsEntry {
     fn last_mod(&self) -> String;
     /// Validate the URL contained in the entry's `<loc>` element.
     fn validate_loc(&self) -> Result<String, Error> {
-        if self.loc().chars().count() > MAX_URL_LENGTH {
-            return Err(Error::UrlValueTooLong);
-        }
-
-        let url = Url::parse(&self.loc())?;
-
-        Ok(url.as_str().into())
+        validate_url(&self.loc())
     }
 }
 
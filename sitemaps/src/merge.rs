@@ -0,0 +1,128 @@
+//! Combine several sitemaps into one, with a single entry per URL.
+//!
+//! ```rust
+//! use sitemaps::merge::{merge, ConflictPolicy};
+//! use sitemaps::sitemap::{Sitemap, UrlEntry};
+//! use sitemaps::Sitemaps;
+//!
+//! let mut entry = UrlEntry::new();
+//! entry.loc = String::from("https://www.example.com/");
+//! let mut first = Sitemap::new();
+//! first.entries.push(entry.clone());
+//! let mut second = Sitemap::new();
+//! second.entries.push(entry);
+//!
+//! let merged = merge(vec![first, second], ConflictPolicy::First).unwrap();
+//! assert_eq!(merged.entries.len(), 1);
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::Error;
+use crate::normalize_loc;
+use crate::sitemap::{Sitemap, UrlEntry};
+use crate::Sitemaps;
+
+/// Which entry to keep when more than one lists the same URL.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ConflictPolicy {
+    /// Keep the entry with the most recent `<lastmod>`. This is the default.
+    NewestLastMod,
+    /// Keep the entry with the highest `<priority>`.
+    HighestPriority,
+    /// Keep the entry read first.
+    First,
+    /// Fail with [`Error::DuplicateLoc`].
+    Error,
+}
+
+impl Default for ConflictPolicy {
+    fn default() -> Self {
+        ConflictPolicy::NewestLastMod
+    }
+}
+
+impl ConflictPolicy {
+    /// Whether `candidate` should replace `kept`. Entries with a value beat those
+    /// without one, and ties keep the entry read first.
+    fn prefers(self, kept: &UrlEntry, candidate: &UrlEntry) -> Result<bool, Error> {
+        let ordering = match self {
+            ConflictPolicy::NewestLastMod => candidate.last_mod.cmp(&kept.last_mod),
+            ConflictPolicy::HighestPriority => candidate.priority.cmp(&kept.priority),
+            ConflictPolicy::First => Ordering::Less,
+            ConflictPolicy::Error => return Err(Error::DuplicateLoc(candidate.loc.clone())),
+        };
+
+        Ok(ordering == Ordering::Greater)
+    }
+}
+
+/// Merge sitemaps into one, keeping a single entry for each normalized `<loc>` as
+/// chosen by `policy`, in the position the URL was first listed. The root element's
+/// namespace and schema attributes are taken from the first sitemap that has them,
+/// and other root attributes are combined, the first value of each winning.
+pub fn merge<I: IntoIterator<Item = Sitemap>>(
+    sitemaps: I,
+    policy: ConflictPolicy,
+) -> Result<Sitemap, Error> {
+    let mut merged = Sitemap::new();
+    let mut namespace = String::new();
+    let mut positions = HashMap::new();
+
+    for sitemap in sitemaps {
+        if namespace.is_empty() {
+            namespace = sitemap.namespace;
+        }
+        if merged.schema_instance.is_none() {
+            merged.schema_instance = sitemap.schema_instance;
+        }
+        if merged.schema_location.is_none() {
+            merged.schema_location = sitemap.schema_location;
+        }
+        for (key, value) in sitemap.attributes {
+            if !merged.attributes.iter().any(|(k, _)| *k == key) {
+                merged.attributes.push((key, value));
+            }
+        }
+
+        for entry in sitemap.entries {
+            let key = normalize_loc(&entry.loc);
+            match positions.get(&key) {
+                Some(&position) => {
+                    if policy.prefers(&merged.entries[position], &entry)? {
+                        merged.entries[position] = entry;
+                    }
+                }
+                None => {
+                    positions.insert(key, merged.entries.len());
+                    merged.entries.push(entry);
+                }
+            }
+        }
+    }
+
+    if !namespace.is_empty() {
+        merged.namespace = namespace;
+    }
+    Ok(merged)
+}
+
+impl Sitemap {
+    /// Merge another sitemap into this one. See [`merge`]. With
+    /// [`ConflictPolicy::Error`], this sitemap is left unchanged if there is a conflict.
+    pub fn merge(&mut self, other: Sitemap, policy: ConflictPolicy) -> Result<(), Error> {
+        if policy == ConflictPolicy::Error {
+            let mut keys = HashSet::new();
+            for entry in self.entries.iter().chain(other.entries.iter()) {
+                if !keys.insert(normalize_loc(&entry.loc)) {
+                    return Err(Error::DuplicateLoc(entry.loc.clone()));
+                }
+            }
+        }
+
+        let this = std::mem::take(self);
+        *self = merge(vec![this, other], policy)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+//! The [Google News sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/news-sitemap),
+//! which adds a `<news:news>` element to a `<url>`.
+
+use chrono::{DateTime, Duration, Utc};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::Writer;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+use crate::error::Error;
+use crate::sitemap::Sitemap;
+use crate::w3c_datetime::W3CDateTime;
+use crate::{
+    namespace_prefix, read_fragment, read_text_content, resolve_element, write_fragment, Extension,
+    Sitemaps, XmlReader,
+};
+
+/// The namespace of the news sitemap extension, bound to the `news` prefix.
+pub const NEWS_NAMESPACE: &str = "http://www.google.com/schemas/sitemap-news/0.9";
+
+/// The maximum number of `<news:news>` entries in a news sitemap.
+pub const MAX_NEWS_URLS: usize = 1_000;
+
+/// The maximum age of an article listed in a news sitemap, in days.
+pub const MAX_ARTICLE_AGE_DAYS: i64 = 2;
+
+/// A news article. It is a parent XML tag containing the required `<news:publication>`,
+/// `<news:publication_date>` and `<news:title>` elements.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct News {
+    /// The publication the article appears in. It is required.
+    pub publication: Publication,
+    /// The date the article was first published. It is required.
+    pub publication_date: Option<W3CDateTime>,
+    /// The title of the article. It is required.
+    pub title: String,
+    /// Optional. Any other child elements of the `<news:news>`, as XML fragments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<Extension>,
+}
+
+/// The publication an article appears in. It is a parent XML tag containing the
+/// required `<news:name>` and `<news:language>` elements.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Publication {
+    /// The name of the publication, as it appears on news.google.com.
+    pub name: String,
+    /// The ISO 639 language code of the publication, or `zh-cn` or `zh-tw`.
+    pub language: String,
+    /// Optional. Any other child elements of the `<news:publication>`, as XML fragments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<Extension>,
+}
+
+impl News {
+    /// Create a new, empty News.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate that the required elements are present, that the language is an
+    /// ISO 639 code, and that the article was published no more than two days
+    /// before `now`.
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), Error> {
+        if self.publication.name.is_empty() {
+            return Err(Error::MissingNewsField("name"));
+        }
+        if self.publication.language.is_empty() {
+            return Err(Error::MissingNewsField("language"));
+        }
+        if self.title.is_empty() {
+            return Err(Error::MissingNewsField("title"));
+        }
+
+        let language = self.publication.language.as_str();
+        let is_iso_639 =
+            (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+        if !is_iso_639 && language != "zh-cn" && language != "zh-tw" {
+            return Err(Error::InvalidNewsLanguage(language.to_string()));
+        }
+
+        let publication_date = self
+            .publication_date
+            .ok_or(Error::MissingNewsField("publication_date"))?;
+        if publication_date.age(now) > Duration::days(MAX_ARTICLE_AGE_DAYS) {
+            return Err(Error::NewsArticleTooOld(publication_date));
+        }
+
+        Ok(())
+    }
+}
+
+/// Read the children of a `<news:news>` element, up to and including its end tag.
+pub(crate) fn read_news<R: BufRead>(reader: &mut XmlReader<R>) -> Result<News, Error> {
+    let mut news = News::new();
+    let mut buf = Vec::new();
+    let mut text_buf = Vec::new();
+    let mut in_publication = false;
+
+    loop {
+        buf.clear();
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => return Err(Error::UnexpectedEof),
+            Event::Start(start) => {
+                let (namespace, local_name) =
+                    resolve_element(reader, start.name(), &[NEWS_NAMESPACE]);
+                match (namespace, local_name.as_ref()) {
+                    (Some(_), b"publication") => {
+                        in_publication = true;
+                        continue;
+                    }
+                    (Some(_), b"name" | b"language" | b"publication_date" | b"title") => {}
+                    _ => {
+                        let extension = read_fragment(reader, &start)?;
+                        if in_publication {
+                            news.publication.extensions.push(extension);
+                        } else {
+                            news.extensions.push(extension);
+                        }
+                        continue;
+                    }
+                }
+
+                let text = read_text_content(reader, &mut text_buf)?;
+                let text = text.trim();
+                match local_name.as_ref() {
+                    b"name" => news.publication.name.push_str(text),
+                    b"language" => news.publication.language.push_str(text),
+                    b"publication_date" => news.publication_date = Some(W3CDateTime::new(text)?),
+                    _ => news.title.push_str(text),
+                }
+            }
+            Event::End(end) => match resolve_element(reader, end.name(), &[NEWS_NAMESPACE]) {
+                (Some(_), name) if name.as_ref() == b"news" => return Ok(news),
+                (Some(_), name) if name.as_ref() == b"publication" => in_publication = false,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Write a `<news:news>` element of the document whose root start tag is `root`,
+/// with the prefix `root` binds to the news namespace.
+pub(crate) fn write_news<W: Write>(
+    writer: &mut Writer<W>,
+    root: &BytesStart,
+    news: &News,
+) -> Result<(), Error> {
+    let prefix = namespace_prefix(root, NEWS_NAMESPACE, "news");
+    let qualified = |local_name: &str| format!("{}:{}", prefix, local_name);
+    let name = qualified("news");
+    writer.write_event(Event::Start(BytesStart::new(name.as_str())))?;
+
+    let publication_name = qualified("publication");
+    writer.write_event(Event::Start(BytesStart::new(publication_name.as_str())))?;
+    Sitemap::write_text_element(writer, qualified("name"), &news.publication.name)?;
+    Sitemap::write_text_element(writer, qualified("language"), &news.publication.language)?;
+    for extension in &news.publication.extensions {
+        write_fragment(writer, root, extension)?;
+    }
+    writer.write_event(Event::End(BytesEnd::new(publication_name)))?;
+
+    if let Some(publication_date) = news.publication_date {
+        Sitemap::write_text_element(
+            writer,
+            qualified("publication_date"),
+            publication_date.to_string(),
+        )?;
+    }
+    Sitemap::write_text_element(writer, qualified("title"), &news.title)?;
+    for extension in &news.extensions {
+        write_fragment(writer, root, extension)?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::io::{self, BufRead, Read};
+
+/// A location in the input of a sitemap being read.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    /// The number of bytes read from the start of the input.
+    pub offset: usize,
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column, counted in bytes from the start of the line.
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {} (byte {})",
+            self.line, self.column, self.offset
+        )
+    }
+}
+
+/// A BufRead wrapper that keeps track of line breaks as bytes are consumed, so that
+/// byte offsets reported by the XML reader can be turned into a [`Position`]. It can
+/// also record the bytes consumed, so that parts of the input can be kept as they are.
+pub(crate) struct LineTracker<R> {
+    inner: R,
+    consumed: usize,
+    line: usize,
+    line_start: usize,
+    capture: Option<Vec<u8>>,
+}
+
+impl<R: BufRead> LineTracker<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            consumed: 0,
+            line: 1,
+            line_start: 0,
+            capture: None,
+        }
+    }
+
+    /// Start recording the bytes consumed.
+    pub(crate) fn start_capture(&mut self) {
+        self.capture = Some(Vec::new());
+    }
+
+    /// Stop recording and return the bytes consumed since [`start_capture`](Self::start_capture).
+    pub(crate) fn take_capture(&mut self) -> Vec<u8> {
+        self.capture.take().unwrap_or_default()
+    }
+
+    /// The position of the given byte offset, which must not be earlier than the
+    /// start of the current line.
+    pub(crate) fn position(&self, offset: usize) -> Position {
+        Position {
+            offset,
+            line: self.line,
+            column: offset.saturating_sub(self.line_start) + 1,
+        }
+    }
+}
+
+impl<R: BufRead> Read for LineTracker<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amt = available.len().min(buf.len());
+        buf[..amt].copy_from_slice(&available[..amt]);
+        self.consume(amt);
+        Ok(amt)
+    }
+}
+
+impl<R: BufRead> BufRead for LineTracker<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The bytes being consumed are still buffered, so this does not read.
+        if let Ok(buf) = self.inner.fill_buf() {
+            let buf = &buf[..amt.min(buf.len())];
+            for (i, byte) in buf.iter().enumerate() {
+                if *byte == b'\n' {
+                    self.line += 1;
+                    self.line_start = self.consumed + i + 1;
+                }
+            }
+            if let Some(ref mut capture) = self.capture {
+                capture.extend_from_slice(buf);
+            }
+        }
+        self.consumed += amt;
+        self.inner.consume(amt);
+    }
+}
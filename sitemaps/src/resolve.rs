@@ -0,0 +1,199 @@
+//! Walk a sitemap index and read the URL entries of every sitemap it lists.
+//!
+//! ```rust
+//! use sitemaps::fetch::FileFetcher;
+//! use sitemaps::resolve::IndexResolver;
+//!
+//! let fetcher = FileFetcher::new("tests/data");
+//! for resolved in IndexResolver::new(fetcher).resolve_location("sitemap.xml") {
+//!     let resolved = resolved.unwrap();
+//!     println!("{} (from {})", resolved.entry.loc, resolved.sitemap);
+//! }
+//! ```
+
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::error::Error;
+use crate::fetch::Fetcher;
+use crate::siteindex::SiteIndex;
+use crate::sitemap::UrlEntry;
+use crate::Document;
+
+/// A URL entry along with the location of the sitemap it was read from.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct ResolvedEntry {
+    pub sitemap: String,
+    pub entry: UrlEntry,
+}
+
+/// Loads the sitemaps listed in a sitemap index through a [`Fetcher`], following
+/// nested indexes up to a maximum depth and stopping after a maximum number of URLs.
+pub struct IndexResolver<F: Fetcher> {
+    fetcher: F,
+    max_depth: usize,
+    max_urls: Option<usize>,
+}
+
+impl<F: Fetcher> IndexResolver<F> {
+    /// Create a resolver that loads sitemaps with `fetcher`.
+    pub fn new(fetcher: F) -> Self {
+        Self {
+            fetcher,
+            max_depth: 1,
+            max_urls: None,
+        }
+    }
+
+    /// Set the maximum number of sitemap indexes above a sitemap. It is 1 by default,
+    /// as the protocol doesn't allow sitemap indexes to list other indexes, so any
+    /// nested index is reported as an [`Error::NestedSiteIndex`].
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Stop after yielding `max_urls` entries.
+    pub fn max_urls(mut self, max_urls: usize) -> Self {
+        self.max_urls = Some(max_urls);
+        self
+    }
+
+    /// Resolve the sitemaps listed in an index that has already been read.
+    pub fn resolve(self, index: &SiteIndex) -> ResolvedEntries<F> {
+        let mut entries = self.into_entries();
+        entries.push_children(index, 1);
+        entries
+    }
+
+    /// Resolve the sitemap or sitemap index at `location`.
+    pub fn resolve_location(self, location: &str) -> ResolvedEntries<F> {
+        let mut entries = self.into_entries();
+        entries.pending.push((location.to_string(), 0));
+        entries
+    }
+
+    fn into_entries(self) -> ResolvedEntries<F> {
+        ResolvedEntries {
+            fetcher: self.fetcher,
+            max_depth: self.max_depth,
+            max_urls: self.max_urls,
+            url_count: 0,
+            pending: vec![],
+            visited: HashSet::new(),
+            current: None,
+        }
+    }
+}
+
+/// Resolve the sitemaps listed in an index with the default limits.
+pub fn resolve_index<F: Fetcher>(fetcher: F, index: &SiteIndex) -> ResolvedEntries<F> {
+    IndexResolver::new(fetcher).resolve(index)
+}
+
+/// The entries of the sitemap being resolved, read as they are needed.
+type SitemapEntries = Box<dyn Iterator<Item = Result<UrlEntry, Error>>>;
+
+/// An iterator over the entries of every sitemap reachable from a sitemap index, in
+/// document order. Sitemaps are read one entry at a time, so that they never have to
+/// be held in memory all at once. A sitemap that cannot be loaded yields an error, and
+/// resolution carries on with the next one.
+pub struct ResolvedEntries<F: Fetcher> {
+    fetcher: F,
+    max_depth: usize,
+    max_urls: Option<usize>,
+    url_count: usize,
+    /// The locations still to load and their depths, the next one last.
+    pending: Vec<(String, usize)>,
+    visited: HashSet<String>,
+    current: Option<(String, SitemapEntries)>,
+}
+
+impl<F: Fetcher> ResolvedEntries<F> {
+    fn push_children(&mut self, index: &SiteIndex, depth: usize) {
+        self.push_locs(index.entries.iter().map(|entry| entry.loc.clone()), depth);
+    }
+
+    fn push_locs<I: DoubleEndedIterator<Item = String>>(&mut self, locs: I, depth: usize) {
+        for loc in locs.rev() {
+            self.pending.push((loc, depth));
+        }
+    }
+
+    /// Load the file at `location`, either making it the current sitemap or queuing
+    /// the children of an index.
+    fn load(&mut self, location: String, depth: usize) -> Result<(), Error> {
+        if !self.visited.insert(location.clone()) {
+            return Err(Error::SitemapCycle(location));
+        }
+
+        let invalid = |e| Error::InvalidSitemap {
+            source: Box::new(e),
+            sitemap: location.clone(),
+        };
+        let document = self
+            .fetcher
+            .fetch(&location)
+            .and_then(Document::open)
+            .map_err(invalid)?;
+
+        match document {
+            Document::Sitemap(reader) => {
+                self.current = Some((location, Box::new(reader)));
+            }
+            Document::Whole(sitemap) => {
+                self.current = Some((location, Box::new(sitemap.entries.into_iter().map(Ok))));
+            }
+            Document::SiteIndex(reader) => {
+                if depth >= self.max_depth {
+                    return Err(Error::NestedSiteIndex(location));
+                }
+                let locs = reader
+                    .map(|entry| entry.map(|entry| entry.loc))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(invalid)?;
+                self.push_locs(locs.into_iter(), depth + 1);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: Fetcher> Iterator for ResolvedEntries<F> {
+    type Item = Result<ResolvedEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.max_urls.map_or(false, |max| self.url_count >= max) {
+                return None;
+            }
+
+            if let Some((ref sitemap, ref mut entries)) = self.current {
+                match entries.next() {
+                    Some(Ok(entry)) => {
+                        self.url_count += 1;
+                        return Some(Ok(ResolvedEntry {
+                            sitemap: sitemap.clone(),
+                            entry,
+                        }));
+                    }
+                    Some(Err(e)) => {
+                        let sitemap = sitemap.clone();
+                        self.current = None;
+                        return Some(Err(Error::InvalidSitemap {
+                            source: Box::new(e),
+                            sitemap,
+                        }));
+                    }
+                    None => self.current = None,
+                }
+            }
+
+            let (location, depth) = self.pending.pop()?;
+            if let Err(e) = self.load(location, depth) {
+                return Some(Err(e));
+            }
+        }
+    }
+}
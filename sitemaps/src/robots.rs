@@ -0,0 +1,278 @@
+//! Read [robots.txt](https://www.rfc-editor.org/rfc/rfc9309.html) files, which
+//! advertise a site's sitemaps with `Sitemap:` lines, and discover the sitemaps of
+//! a site through them.
+//!
+//! ```rust
+//! use sitemaps::robots::Robots;
+//!
+//! let robots = Robots::parse("User-agent: *\nDisallow: /private/\n\nSitemap: https://www.example.com/sitemap.xml\n");
+//! assert_eq!(robots.sitemaps, vec!["https://www.example.com/sitemap.xml"]);
+//! assert_eq!(robots.groups[0].rules[0].path, "/private/");
+//! assert!(!robots.is_allowed("Googlebot", "https://www.example.com/private/page.html"));
+//! ```
+
+use serde::Serialize;
+use std::io::BufRead;
+use std::path::Path;
+use url::Url;
+
+use crate::error::Error;
+use crate::fetch::Fetcher;
+use crate::{SitemapsFile, BOM};
+
+/// The name of the robots.txt file at the root of a site.
+pub const ROBOTS_TXT: &str = "robots.txt";
+
+/// The contents of a robots.txt file.
+#[derive(Debug, Default, PartialEq, Clone, Serialize)]
+pub struct Robots {
+    /// The groups of rules, in file order.
+    pub groups: Vec<Group>,
+    /// The values of the `Sitemap:` lines, which apply to the whole file.
+    pub sitemaps: Vec<String>,
+}
+
+/// A group of rules that applies to the crawlers named by its `User-agent:` lines.
+#[derive(Debug, Default, PartialEq, Clone, Serialize)]
+pub struct Group {
+    pub user_agents: Vec<String>,
+    pub rules: Vec<Rule>,
+}
+
+/// An `Allow:` or `Disallow:` line.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Rule {
+    pub kind: RuleKind,
+    /// The path pattern, which may contain `*` and `$` wildcards.
+    pub path: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum RuleKind {
+    Allow,
+    Disallow,
+}
+
+impl Robots {
+    /// Parse the text of a robots.txt file. Lines that aren't understood, and rules
+    /// that come before any `User-agent:` line, are ignored.
+    pub fn parse(text: &str) -> Self {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut robots = Robots::default();
+        let mut in_user_agents = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+
+            if key.eq_ignore_ascii_case("user-agent") {
+                if !in_user_agents {
+                    robots.groups.push(Group::default());
+                    in_user_agents = true;
+                }
+                if let Some(group) = robots.groups.last_mut() {
+                    group.user_agents.push(value.to_string());
+                }
+                continue;
+            }
+
+            let kind = if key.eq_ignore_ascii_case("allow") {
+                RuleKind::Allow
+            } else if key.eq_ignore_ascii_case("disallow") {
+                RuleKind::Disallow
+            } else {
+                if key.eq_ignore_ascii_case("sitemap") && !value.is_empty() {
+                    robots.sitemaps.push(value.to_string());
+                }
+                continue;
+            };
+
+            // Only a rule ends a run of `User-agent:` lines, so other lines between
+            // them, such as `Sitemap:`, don't split the group.
+            in_user_agents = false;
+
+            // An empty path matches nothing, so `Disallow:` on its own allows everything.
+            if value.is_empty() {
+                continue;
+            }
+            if let Some(group) = robots.groups.last_mut() {
+                group.rules.push(Rule {
+                    kind,
+                    path: value.to_string(),
+                });
+            }
+        }
+
+        robots
+    }
+
+    /// Return the rules that apply to a crawler. As with Google, the crawler follows
+    /// the groups naming the most specific user agent that matches its name, case
+    /// insensitively, or otherwise the `*` groups. Matching groups are combined.
+    pub fn rules_for(&self, user_agent: &str) -> Vec<&Rule> {
+        // Only the product token of a full user agent string, such as
+        // `Googlebot` in `Googlebot/2.1`, is matched.
+        let product = user_agent
+            .split(|c: char| c == '/' || c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        let matching = |agent: &String| {
+            let agent = agent.to_ascii_lowercase();
+            agent != "*" && !agent.is_empty() && product.starts_with(&agent)
+        };
+        let best = self
+            .groups
+            .iter()
+            .flat_map(|group| group.user_agents.iter())
+            .filter(|agent| matching(agent))
+            .map(String::len)
+            .max();
+
+        let applies = |group: &&Group| match best {
+            Some(len) => group
+                .user_agents
+                .iter()
+                .any(|agent| matching(agent) && agent.len() == len),
+            None => group.user_agents.iter().any(|agent| agent == "*"),
+        };
+        self.groups
+            .iter()
+            .filter(applies)
+            .flat_map(|group| group.rules.iter())
+            .collect()
+    }
+
+    /// Check whether a crawler may fetch a URL, or a path with an optional query.
+    /// The rule with the longest path matching the URL's path decides, with `Allow`
+    /// winning a tie, and a URL no rule matches is allowed. `/robots.txt` itself is
+    /// always allowed.
+    pub fn is_allowed(&self, user_agent: &str, loc: &str) -> bool {
+        let path = match Url::parse(loc) {
+            Ok(url) => match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            },
+            Err(_) => loc.to_string(),
+        };
+        if path == format!("/{}", ROBOTS_TXT) {
+            return true;
+        }
+
+        let decisive = self
+            .rules_for(user_agent)
+            .into_iter()
+            .filter(|rule| rule.matches(&path))
+            .max_by_key(|rule| (rule.path.len(), rule.kind == RuleKind::Allow));
+
+        decisive.map_or(true, |rule| rule.kind == RuleKind::Allow)
+    }
+
+    /// Read and parse a robots.txt file. Invalid UTF-8 is replaced rather than rejected.
+    pub fn read<R: BufRead>(mut reader: R) -> Result<Self, Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+        let buf = buf.strip_prefix(BOM).unwrap_or(&buf);
+
+        Ok(Self::parse(&String::from_utf8_lossy(buf)))
+    }
+}
+
+impl Rule {
+    /// Check whether the rule's path pattern matches a path. A pattern matches any
+    /// path it is a prefix of, where `*` matches any sequence of characters and a
+    /// trailing `$` anchors the pattern to the end of the path.
+    pub fn matches(&self, path: &str) -> bool {
+        let (pattern, anchored) = match self.path.strip_suffix('$') {
+            Some(pattern) => (pattern, true),
+            None => (self.path.as_str(), false),
+        };
+
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or_default();
+        let mut rest = match path.strip_prefix(first) {
+            Some(rest) => rest,
+            None => return false,
+        };
+
+        let parts: Vec<&str> = parts.collect();
+        let (last, middle) = match parts.split_last() {
+            Some(split) => split,
+            None => return !anchored || rest.is_empty(),
+        };
+
+        for part in middle {
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+
+        if anchored {
+            rest.ends_with(last)
+        } else {
+            rest.contains(last)
+        }
+    }
+}
+
+/// The result of [`discover`]ing a site's sitemaps.
+#[derive(Debug)]
+pub struct Discovery {
+    /// The location robots.txt was read from.
+    pub location: String,
+    pub robots: Robots,
+    /// Each sitemap advertised by robots.txt, in file order.
+    pub sitemaps: Vec<DiscoveredSitemap>,
+}
+
+/// A sitemap advertised by robots.txt, or the error that occurred loading it.
+#[derive(Debug)]
+pub struct DiscoveredSitemap {
+    pub location: String,
+    pub file: Result<SitemapsFile, Error>,
+}
+
+/// Return the location of a site's robots.txt file. `site_root` may be the URL of the
+/// site or the path of a directory, or the location of the robots.txt file itself.
+pub fn robots_location(site_root: &str) -> Result<String, Error> {
+    if site_root.ends_with(ROBOTS_TXT) {
+        return Ok(site_root.to_string());
+    }
+
+    match Url::parse(site_root) {
+        // A single letter scheme is a Windows drive letter rather than a URL.
+        Ok(url) if url.scheme().len() > 1 => Ok(url.join(&format!("/{}", ROBOTS_TXT))?.into()),
+        _ => Ok(Path::new(site_root)
+            .join(ROBOTS_TXT)
+            .to_string_lossy()
+            .into_owned()),
+    }
+}
+
+/// Load a site's robots.txt through `fetcher`, then each sitemap or sitemap index it
+/// advertises. Only the failure to load robots.txt itself is returned as an error;
+/// a sitemap that cannot be loaded is reported in its [`DiscoveredSitemap`].
+pub fn discover<F: Fetcher>(mut fetcher: F, site_root: &str) -> Result<Discovery, Error> {
+    let location = robots_location(site_root)?;
+    let robots = Robots::read(fetcher.fetch(&location)?)?;
+
+    let sitemaps = robots
+        .sitemaps
+        .iter()
+        .map(|sitemap| DiscoveredSitemap {
+            location: sitemap.clone(),
+            file: fetcher.fetch(sitemap).and_then(SitemapsFile::read),
+        })
+        .collect();
+
+    Ok(Discovery {
+        location,
+        robots,
+        sitemaps,
+    })
+}
@@ -1,22 +1,55 @@
-use crate::{Entries, Sitemaps, SitemapsEntry, NAMESPACE};
+use crate::{
+    read_fragment, read_root, read_text_content, with_position, write_fragment, xml_reader,
+    Entries, Extension, Sitemaps, SitemapsEntry, XmlReader, MAX_ENTRIES, NAMESPACE,
+};
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
-use quick_xml::{Reader, Writer};
-use serde::Serialize;
+use quick_xml::Writer;
+use serde::{Deserialize, Serialize};
 use std::io::prelude::*;
 use std::io::BufRead;
 
 use crate::error::Error;
 use crate::w3c_datetime::W3CDateTime;
 
-#[derive(Debug, Default, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct SiteIndex {
     pub entries: Vec<SitemapEntry>,
     pub schema_instance: Option<String>,
     pub schema_location: Option<String>,
+    /// The namespace of the root element, the sitemaps.org one for a new document or
+    /// empty if the document read declared none.
     pub namespace: String,
+    /// Any other attributes of the `<sitemapindex>` element, such as extension namespace declarations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<(String, String)>,
 }
 
-impl SiteIndex {}
+/// The name of a sitemap index's root element.
+pub(crate) const ROOT_NAME: &str = "sitemapindex";
+
+impl SiteIndex {
+    /// Build the `<sitemapindex>` start tag.
+    pub(crate) fn root_element(&self) -> BytesStart<'_> {
+        let mut element = BytesStart::new(ROOT_NAME);
+        if let Some(ref schema_instance) = self.schema_instance {
+            element.push_attribute(("xmlns:xsi", schema_instance.as_str()));
+        }
+        if let Some(ref schema_location) = self.schema_location {
+            element.push_attribute(("xsi:schemaLocation", schema_location.as_str()));
+        }
+        let namespace = if self.namespace.is_empty() {
+            NAMESPACE
+        } else {
+            self.namespace.as_str()
+        };
+        element.push_attribute(("xmlns", namespace));
+        for (key, value) in &self.attributes {
+            element.push_attribute((key.as_str(), value.as_str()));
+        }
+
+        element
+    }
+}
 
 impl Entries for SiteIndex {
     fn locs(&self) -> Vec<String> {
@@ -35,10 +68,13 @@ impl Entries for SiteIndex {
 
 /// A entry in a sitemap index file. It is a parent XML tag containing the required `<loc>` element
 /// and the `<lastmod>` elements.
-#[derive(Debug, Default, PartialEq, Serialize)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct SitemapEntry {
     pub loc: String,
     pub last_mod: Option<W3CDateTime>,
+    /// Any other child elements of the `<sitemap>`, as XML fragments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<Extension>,
 }
 
 impl SitemapEntry {
@@ -46,8 +82,32 @@ impl SitemapEntry {
         Self {
             loc: String::new(),
             last_mod: None,
+            extensions: vec![],
         }
     }
+
+    /// Write the entry as a `<sitemap>` element of the document whose root start tag is `root`.
+    pub(crate) fn write<W: Write>(
+        &self,
+        writer: &mut Writer<W>,
+        root: &BytesStart,
+    ) -> Result<(), Error> {
+        let name = "sitemap";
+        writer.write_event(Event::Start(BytesStart::new(name)))?;
+
+        SiteIndex::write_text_element(writer, "loc", &self.loc)?;
+
+        if let Some(lastmod) = self.last_mod {
+            SiteIndex::write_text_element(writer, "lastmod", lastmod.to_string())?;
+        }
+
+        for extension in &self.extensions {
+            write_fragment(writer, root, extension)?;
+        }
+
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
 }
 
 impl SitemapsEntry for SitemapEntry {
@@ -63,127 +123,207 @@ impl SitemapsEntry for SitemapEntry {
     }
 }
 
-impl Sitemaps for SiteIndex {
-    fn new() -> Self {
-        Self {
-            entries: vec![],
-            schema_location: None,
+/// A pull-based reader over the `<sitemap>` entries of a sitemap index.
+///
+/// The attributes of the `<sitemapindex>` root element are read when the reader
+/// is created, and entries are parsed one at a time as the iterator is advanced.
+pub struct SiteIndexReader<R: BufRead> {
+    reader: XmlReader<R>,
+    buf: Vec<u8>,
+    nested_buf: Vec<u8>,
+    schema_instance: Option<String>,
+    schema_location: Option<String>,
+    namespace: String,
+    attributes: Vec<(String, String)>,
+    entry_count: usize,
+    in_entry: bool,
+    done: bool,
+}
+
+impl<R: BufRead> SiteIndexReader<R> {
+    /// Create a new SiteIndexReader, reading up to and including the `<sitemapindex>` root element.
+    pub fn new(reader: R) -> Result<Self, Error> {
+        let mut reader = xml_reader(reader);
+        let root = read_root(&mut reader).map_err(|e| with_position(&reader, None, e))?;
+        if root.name().as_ref() != b"sitemapindex" {
+            return Err(Error::NotASitemap);
+        }
+
+        Self::from_root(reader, &root)
+    }
+
+    /// Create a new SiteIndexReader from an XML reader positioned just after the `<sitemapindex>` root element.
+    pub(crate) fn from_root(reader: XmlReader<R>, root: &BytesStart) -> Result<Self, Error> {
+        let mut index_reader = Self {
+            reader,
+            buf: Vec::new(),
+            nested_buf: Vec::new(),
             schema_instance: None,
+            schema_location: None,
             namespace: String::new(),
+            attributes: vec![],
+            entry_count: 0,
+            in_entry: false,
+            done: false,
+        };
+
+        for attr_result in root.attributes() {
+            let a = attr_result.map_err(|e| with_position(&index_reader.reader, None, e.into()))?;
+            let value = a
+                .decode_and_unescape_value(&index_reader.reader)
+                .map_err(|e| with_position(&index_reader.reader, None, e.into()))?
+                .to_string();
+            match a.key.as_ref() {
+                b"xmlns:xsi" => index_reader.schema_instance = Some(value),
+                b"xsi:schemaLocation" => index_reader.schema_location = Some(value),
+                b"xmlns" => index_reader.namespace = value,
+                key => index_reader
+                    .attributes
+                    .push((String::from_utf8_lossy(key).into_owned(), value)),
+            }
         }
+
+        Ok(index_reader)
     }
 
-    fn read_from<R: BufRead>(reader: R) -> Result<Self, Error> {
-        let mut reader = Reader::from_reader(reader);
-        reader.trim_text(true).expand_empty_elements(true);
+    /// The value of the `xmlns:xsi` attribute of the `<sitemapindex>` element, if any.
+    pub fn schema_instance(&self) -> Option<&str> {
+        self.schema_instance.as_deref()
+    }
+
+    /// The value of the `xsi:schemaLocation` attribute of the `<sitemapindex>` element, if any.
+    pub fn schema_location(&self) -> Option<&str> {
+        self.schema_location.as_deref()
+    }
+
+    /// The value of the `xmlns` attribute of the `<sitemapindex>` element.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Any other attributes of the `<sitemapindex>` element, in document order.
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    /// Read all remaining entries into a [`SiteIndex`].
+    pub fn into_site_index(mut self) -> Result<SiteIndex, Error> {
+        let mut sitemap_index = SiteIndex {
+            entries: vec![],
+            schema_instance: self.schema_instance.take(),
+            schema_location: self.schema_location.take(),
+            namespace: std::mem::take(&mut self.namespace),
+            attributes: std::mem::take(&mut self.attributes),
+        };
 
-        let mut buf = Vec::new();
-        let mut nested_buf = Vec::new();
-        let mut sitemap_index = SiteIndex::new();
+        for entry in self {
+            sitemap_index.entries.push(entry?);
+        }
 
+        Ok(sitemap_index)
+    }
+
+    /// Read the next `<sitemap>` element, returning `None` at the end of the document.
+    fn read_entry(&mut self) -> Result<Option<SitemapEntry>, Error> {
         let mut entry = SitemapEntry::new();
-        let mut entry_count: u32 = 0;
+
         loop {
-            match reader.read_event_into(&mut buf) {
-                Err(e) => panic!("Error at position {}: {:?}", reader.buffer_position(), e),
-                Ok(Event::Eof) => break,
-                Ok(Event::Decl(e)) => Self::check_encoding(e)?,
-                Ok(Event::Start(start)) => {
-                    if start.name().as_ref() == b"sitemapindex" {
-                        for attr_result in start.attributes() {
-                            let a = attr_result?;
-                            match a.key.as_ref() {
-                                b"xmlns:xsi" => {
-                                    sitemap_index.schema_instance =
-                                        Some(a.decode_and_unescape_value(&reader)?.to_string());
-                                }
-                                b"xsi:schemaLocation" => {
-                                    sitemap_index.schema_location =
-                                        Some(a.decode_and_unescape_value(&reader)?.to_string());
-                                }
-                                b"xmlns" => {
-                                    sitemap_index.namespace =
-                                        a.decode_and_unescape_value(&reader)?.to_string();
-                                }
-                                _ => {}
-                            }
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Eof => return Ok(None),
+                Event::Start(start) => match start.name().as_ref() {
+                    b"sitemap" => self.in_entry = true,
+                    name @ (b"loc" | b"lastmod") => {
+                        let text = read_text_content(&mut self.reader, &mut self.nested_buf)?;
+                        if text.is_empty() {
+                            continue;
                         }
-                    }
 
-                    if start.name().as_ref() == b"sitemap" {
-                        continue;
+                        match name {
+                            b"loc" => entry.loc.push_str(&text),
+                            _ => entry.last_mod = Some(W3CDateTime::new(&text)?),
+                        }
                     }
-
-                    loop {
-                        match reader.read_event_into(&mut nested_buf) {
-                            Err(e) => {
-                                panic!("Error at position {}: {:?}", reader.buffer_position(), e)
-                            }
-                            Ok(Event::Text(e)) => {
-                                let text = e.unescape()?.to_string();
-                                match start.name().as_ref() {
-                                    b"loc" => entry.loc.push_str(&text),
-                                    b"lastmod" => entry.last_mod = Some(W3CDateTime::new(&text)?),
-                                    _ => {}
-                                }
-                            }
-                            _ => break,
+                    _ => {
+                        let fragment = read_fragment(&mut self.reader, &start)?;
+                        if self.in_entry {
+                            entry.extensions.push(fragment);
                         }
                     }
-                }
-
-                Ok(Event::End(e)) => {
-                    if e.name().as_ref() == b"sitemap" {
-                        entry_count += 1;
+                },
+                Event::End(e) if e.name().as_ref() == b"sitemap" => {
+                    if self.entry_count >= MAX_ENTRIES {
+                        return Err(Error::TooManyUrls);
+                    }
 
-                        if entry_count > 50_000 {
-                            return Err(Error::TooManyUrls);
-                        }
+                    self.in_entry = false;
+                    self.entry_count += 1;
 
-                        sitemap_index.entries.push(entry);
-                        entry = SitemapEntry::new();
-                    }
+                    return Ok(Some(entry));
                 }
                 _ => {}
             }
-            buf.clear();
         }
-
-        Ok(sitemap_index)
     }
-    fn write<W: Write>(&self, mut writer: Writer<W>) -> Result<W, Error> {
-        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+}
 
-        let name = "sitemapindex";
-        let mut element = BytesStart::new(name);
-        if let Some(ref schema_instance) = self.schema_instance {
-            element.push_attribute(("xmlns:xsi", schema_instance.as_str()));
+impl<R: BufRead> Iterator for SiteIndexReader<R> {
+    type Item = Result<SitemapEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
         }
-        if let Some(ref schema_location) = self.schema_location {
-            element.push_attribute(("xsi:schemaLocation", schema_location.as_str()));
+
+        match self.read_entry() {
+            Ok(Some(entry)) => Some(Ok(entry)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                let entry = if self.in_entry {
+                    Some(self.entry_count)
+                } else {
+                    None
+                };
+                Some(Err(with_position(&self.reader, entry, e)))
+            }
         }
-        let namespace = if self.namespace.is_empty() {
-            NAMESPACE
-        } else {
-            self.namespace.as_str()
-        };
-        element.push_attribute(("xmlns", namespace));
-        writer.write_event(Event::Start(element))?;
+    }
+}
 
-        for entry in &self.entries {
-            let inner_name = "sitemap";
-            writer.write_event(Event::Start(BytesStart::new(inner_name)))?;
+impl Default for SiteIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-            Self::write_text_element(&mut writer, "loc", entry.loc.clone())?;
+impl Sitemaps for SiteIndex {
+    fn new() -> Self {
+        Self {
+            entries: vec![],
+            schema_location: None,
+            schema_instance: None,
+            namespace: NAMESPACE.to_string(),
+            attributes: vec![],
+        }
+    }
 
-            if let Some(lastmod) = entry.last_mod {
-                Self::write_text_element(&mut writer, "lastmod", lastmod.to_string())?;
-            }
+    fn read_from<R: BufRead>(reader: R) -> Result<Self, Error> {
+        SiteIndexReader::new(reader)?.into_site_index()
+    }
+    fn write<W: Write>(&self, mut writer: Writer<W>) -> Result<W, Error> {
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        let root = self.root_element();
+        writer.write_event(Event::Start(root.borrow()))?;
 
-            writer.write_event(Event::End(BytesEnd::new(inner_name)))?;
+        for entry in &self.entries {
+            entry.write(&mut writer, &root)?;
         }
 
-        writer.write_event(Event::End(BytesEnd::new(name)))?;
+        writer.write_event(Event::End(BytesEnd::new(ROOT_NAME)))?;
         Ok(writer.into_inner())
     }
 }
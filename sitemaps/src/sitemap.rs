@@ -1,152 +1,459 @@
-use crate::{Entries, SitemapsEntry, NAMESPACE};
+use crate::hreflang::{read_alternate, write_alternate, Alternate, XHTML_NAMESPACE};
+use crate::image::{read_image, write_image, Image, IMAGE_NAMESPACE, MAX_IMAGES};
+use crate::news::{read_news, write_news, News, MAX_NEWS_URLS, NEWS_NAMESPACE};
+use crate::position::Position;
+use crate::video::{read_video, write_video, Video, VIDEO_NAMESPACE};
+use crate::{
+    read_fragment, read_root, read_text_content, resolve_element, with_position, write_fragment,
+    xml_reader, Entries, Extension, SitemapsEntry, XmlReader, MAX_ENTRIES, MAX_FILE_SIZE,
+    NAMESPACE,
+};
+use chrono::{DateTime, Utc};
 use core::fmt;
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
-use quick_xml::reader::Reader;
 use quick_xml::writer::Writer;
-use serde::Serialize;
-use std::io::{BufRead, Write};
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::io::{BufRead, Read, Write};
+use std::str::FromStr;
 
 use crate::{error::Error, w3c_datetime::W3CDateTime, Sitemaps};
 
+/// The name of a sitemap's root element.
+pub(crate) const ROOT_NAME: &str = "urlset";
+
+/// The namespaces of the sitemap extensions that are read into typed values.
+const EXTENSIONS: &[&str] = &[
+    IMAGE_NAMESPACE,
+    VIDEO_NAMESPACE,
+    NEWS_NAMESPACE,
+    XHTML_NAMESPACE,
+];
+
 /// A Sitemap is an entity-escaped, UTF-8 encoded list of `<url>` elements contained
 /// in a `<urlset>` element.
-#[derive(Debug, Default, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Sitemap {
     /// The set of URLs in the sitemap.
     pub schema_instance: Option<String>,
     pub schema_location: Option<String>,
+    /// The namespace of the root element, the sitemaps.org one for a new document or
+    /// empty if the document read declared none.
     pub namespace: String,
+    /// Any other attributes of the `<urlset>` element, such as extension namespace declarations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<(String, String)>,
     pub entries: Vec<UrlEntry>,
 }
 
+impl Sitemap {
+    /// Read a sitemap in the text format: a UTF-8 encoded file with one absolute
+    /// URL per line and nothing else. Blank lines are ignored. Each URL is validated
+    /// as a `<loc>` would be, and the file must not exceed 50,000 URLs or 50MB.
+    pub fn read_text<R: BufRead>(mut reader: R) -> Result<Sitemap, Error> {
+        let mut sitemap = Sitemap::new();
+        let mut line = String::new();
+        let mut line_number = 0;
+        let mut offset = 0;
+
+        loop {
+            line.clear();
+            line_number += 1;
+            let position = Position {
+                offset,
+                line: line_number,
+                column: 1,
+            };
+            let entry = sitemap.entries.len();
+            let positioned = |e: Error| Error::ParseError {
+                source: Box::new(e),
+                position,
+                entry: Some(entry),
+            };
+
+            // Read no more than one byte past the size limit, however long the line.
+            let read = (&mut reader)
+                .take((MAX_FILE_SIZE - offset + 1) as u64)
+                .read_line(&mut line)
+                .map_err(|e| positioned(e.into()))?;
+            if read == 0 {
+                break;
+            }
+
+            offset += read;
+            if offset > MAX_FILE_SIZE {
+                return Err(positioned(Error::FileTooLarge));
+            }
+
+            let text = if line_number == 1 {
+                line.trim_start_matches('\u{feff}')
+            } else {
+                line.as_str()
+            }
+            .trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            if entry >= MAX_ENTRIES {
+                return Err(positioned(Error::TooManyUrls));
+            }
+
+            let mut url = UrlEntry::new();
+            url.loc.push_str(text);
+            url.validate_loc().map_err(positioned)?;
+            sitemap.entries.push(url);
+        }
+
+        Ok(sitemap)
+    }
+
+    /// Validate the news sitemap rules: there must be no more than 1,000 entries
+    /// with a `<news:news>` element, and each must be valid and published no more
+    /// than two days before `now`.
+    pub fn validate_news(&self, now: DateTime<Utc>) -> Result<(), Error> {
+        let news_count = self.entries.iter().filter(|e| e.news.is_some()).count();
+        if news_count > MAX_NEWS_URLS {
+            return Err(Error::TooManyNewsUrls);
+        }
+
+        for (entry, url) in self.entries.iter().enumerate() {
+            if let Some(ref news) = url.news {
+                news.validate(now).map_err(|e| Error::InvalidEntry {
+                    source: Box::new(e),
+                    entry,
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the `<urlset>` start tag. The namespaces of the sitemap extensions used by
+    /// its entries are declared if they aren't already, or of all extensions if
+    /// `all_extensions` is set, for when the entries aren't known in advance. An
+    /// extension's usual prefix is numbered if it is bound to another namespace.
+    pub(crate) fn root_element(&self, all_extensions: bool) -> BytesStart<'_> {
+        let mut element = BytesStart::new(ROOT_NAME);
+        if let Some(ref schema_instance) = self.schema_instance {
+            element.push_attribute(("xmlns:xsi", schema_instance.as_str()));
+        }
+        if let Some(ref schema_location) = self.schema_location {
+            element.push_attribute(("xsi:schemaLocation", schema_location.as_str()));
+        }
+        let namespace = if self.namespace.is_empty() {
+            NAMESPACE
+        } else {
+            self.namespace.as_str()
+        };
+        element.push_attribute(("xmlns", namespace));
+        for (key, value) in &self.attributes {
+            element.push_attribute((key.as_str(), value.as_str()));
+        }
+
+        let uses = |used: fn(&UrlEntry) -> bool| all_extensions || self.entries.iter().any(used);
+        let extensions = [
+            ("image", IMAGE_NAMESPACE, uses(|e| !e.images.is_empty())),
+            ("video", VIDEO_NAMESPACE, uses(|e| !e.videos.is_empty())),
+            ("news", NEWS_NAMESPACE, uses(|e| e.news.is_some())),
+            ("xhtml", XHTML_NAMESPACE, uses(|e| !e.alternates.is_empty())),
+        ];
+        for (prefix, extension_namespace, used) in extensions {
+            let declared = self
+                .attributes
+                .iter()
+                .any(|(k, v)| k.starts_with("xmlns:") && v == extension_namespace);
+            if !used || declared {
+                continue;
+            }
+
+            let mut key = format!("xmlns:{}", prefix);
+            let mut number = 1;
+            while element
+                .attributes()
+                .flatten()
+                .any(|attr| attr.key.as_ref() == key.as_bytes())
+            {
+                number += 1;
+                key = format!("xmlns:{}{}", prefix, number);
+            }
+            element.push_attribute((key.as_str(), extension_namespace));
+        }
+
+        element
+    }
+
+    /// Write the sitemap's URLs in the text format, one per line. Each URL is
+    /// validated as a `<loc>` would be and written in normalized form, and the file
+    /// must not exceed 50,000 URLs or 50MB.
+    pub fn write_text<W: Write>(&self, mut writer: W) -> Result<W, Error> {
+        if self.entries.len() > MAX_ENTRIES {
+            return Err(Error::TooManyUrls);
+        }
+
+        let mut size = 0;
+        for (entry, url_entry) in self.entries.iter().enumerate() {
+            let invalid_entry = |e: Error| Error::InvalidEntry {
+                source: Box::new(e),
+                entry,
+            };
+
+            let loc = url_entry.validate_loc().map_err(invalid_entry)?;
+            size += loc.len() + 1;
+            if size > MAX_FILE_SIZE {
+                return Err(invalid_entry(Error::FileTooLarge));
+            }
+            writeln!(writer, "{}", loc)?;
+        }
+
+        Ok(writer)
+    }
+}
+
+impl Default for Sitemap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Sitemaps for Sitemap {
     fn new() -> Self {
         Self {
             schema_instance: None,
             schema_location: None,
-            namespace: String::new(),
+            namespace: NAMESPACE.to_string(),
+            attributes: vec![],
             entries: vec![],
         }
     }
 
     fn read_from<R: BufRead>(reader: R) -> Result<Sitemap, Error> {
-        let mut reader = Reader::from_reader(reader);
-        reader.trim_text(true).expand_empty_elements(true);
+        SitemapReader::new(reader)?.into_sitemap()
+    }
+    fn write<W: Write>(&self, mut writer: Writer<W>) -> Result<W, Error> {
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        let root = self.root_element(false);
+        writer.write_event(Event::Start(root.borrow()))?;
 
-        let mut buf = Vec::new();
-        let mut nested_buf = Vec::new();
+        for url_entry in &self.entries {
+            url_entry.write(&mut writer, &root)?;
+        }
 
-        let mut sitemap = Sitemap::new();
+        writer.write_event(Event::End(BytesEnd::new(ROOT_NAME)))?;
+        Ok(writer.into_inner())
+    }
+}
 
+/// A pull-based reader over the `<url>` entries of a sitemap.
+///
+/// The attributes of the `<urlset>` root element are read when the reader is
+/// created, and entries are parsed one at a time as the iterator is advanced,
+/// so a sitemap never has to be held in memory all at once.
+///
+/// ```rust
+/// use std::fs::File;
+/// use std::io::BufReader;
+/// use sitemaps::sitemap::SitemapReader;
+///
+/// let file = File::open("tests/data/example_2_url.xml").unwrap();
+/// let reader = SitemapReader::new(BufReader::new(file)).unwrap();
+/// assert_eq!(reader.namespace(), sitemaps::NAMESPACE);
+///
+/// for entry in reader {
+///     println!("{}", entry.unwrap().loc);
+/// }
+/// ```
+pub struct SitemapReader<R: BufRead> {
+    reader: XmlReader<R>,
+    buf: Vec<u8>,
+    nested_buf: Vec<u8>,
+    schema_instance: Option<String>,
+    schema_location: Option<String>,
+    namespace: String,
+    attributes: Vec<(String, String)>,
+    entry_count: usize,
+    in_entry: bool,
+    done: bool,
+}
+
+impl<R: BufRead> SitemapReader<R> {
+    /// Create a new SitemapReader, reading up to and including the `<urlset>` root element.
+    pub fn new(reader: R) -> Result<Self, Error> {
+        let mut reader = xml_reader(reader);
+        let root = read_root(&mut reader).map_err(|e| with_position(&reader, None, e))?;
+        if root.name().as_ref() != b"urlset" {
+            return Err(Error::NotASitemap);
+        }
+
+        Self::from_root(reader, &root)
+    }
+
+    /// Create a new SitemapReader from an XML reader positioned just after the `<urlset>` root element.
+    pub(crate) fn from_root(reader: XmlReader<R>, root: &BytesStart) -> Result<Self, Error> {
+        let mut sitemap_reader = Self {
+            reader,
+            buf: Vec::new(),
+            nested_buf: Vec::new(),
+            schema_instance: None,
+            schema_location: None,
+            namespace: String::new(),
+            attributes: vec![],
+            entry_count: 0,
+            in_entry: false,
+            done: false,
+        };
+
+        for attr_result in root.attributes() {
+            let a =
+                attr_result.map_err(|e| with_position(&sitemap_reader.reader, None, e.into()))?;
+            let value = a
+                .decode_and_unescape_value(&sitemap_reader.reader)
+                .map_err(|e| with_position(&sitemap_reader.reader, None, e.into()))?
+                .to_string();
+            match a.key.as_ref() {
+                b"xmlns:xsi" => sitemap_reader.schema_instance = Some(value),
+                b"xsi:schemaLocation" => sitemap_reader.schema_location = Some(value),
+                b"xmlns" => sitemap_reader.namespace = value,
+                key => sitemap_reader
+                    .attributes
+                    .push((String::from_utf8_lossy(key).into_owned(), value)),
+            }
+        }
+
+        Ok(sitemap_reader)
+    }
+
+    /// The value of the `xmlns:xsi` attribute of the `<urlset>` element, if any.
+    pub fn schema_instance(&self) -> Option<&str> {
+        self.schema_instance.as_deref()
+    }
+
+    /// The value of the `xsi:schemaLocation` attribute of the `<urlset>` element, if any.
+    pub fn schema_location(&self) -> Option<&str> {
+        self.schema_location.as_deref()
+    }
+
+    /// The value of the `xmlns` attribute of the `<urlset>` element.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Any other attributes of the `<urlset>` element, in document order.
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    /// Read all remaining entries into a [`Sitemap`].
+    pub fn into_sitemap(mut self) -> Result<Sitemap, Error> {
+        let mut sitemap = Sitemap {
+            schema_instance: self.schema_instance.take(),
+            schema_location: self.schema_location.take(),
+            namespace: std::mem::take(&mut self.namespace),
+            attributes: std::mem::take(&mut self.attributes),
+            entries: vec![],
+        };
+
+        for entry in self {
+            sitemap.entries.push(entry?);
+        }
+
+        Ok(sitemap)
+    }
+
+    /// Read the next `<url>` element, returning `None` at the end of the document.
+    fn read_entry(&mut self) -> Result<Option<UrlEntry>, Error> {
         let mut url = UrlEntry::new();
+
         loop {
-            match reader.read_event_into(&mut buf) {
-                Err(e) => panic!("Error at position {}: {:?}", reader.buffer_position(), e),
-                Ok(Event::Eof) => break,
-                Ok(Event::Decl(e)) => Self::check_encoding(e)?,
-                Ok(Event::Start(start)) => {
-                    if start.name().as_ref() == b"urlset" {
-                        for attr_result in start.attributes() {
-                            let a = attr_result?;
-                            match a.key.as_ref() {
-                                b"xmlns:xsi" => {
-                                    sitemap.schema_instance =
-                                        Some(a.decode_and_unescape_value(&reader)?.to_string());
-                                }
-                                b"xsi:schemaLocation" => {
-                                    sitemap.schema_location =
-                                        Some(a.decode_and_unescape_value(&reader)?.to_string());
-                                }
-                                b"xmlns" => {
-                                    sitemap.namespace =
-                                        a.decode_and_unescape_value(&reader)?.to_string();
-                                }
-                                _ => {}
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Eof => return Ok(None),
+                Event::Start(start) => {
+                    let (extension, local_name) =
+                        resolve_element(&self.reader, start.name(), EXTENSIONS);
+                    match (extension, local_name.as_ref()) {
+                        (Some(XHTML_NAMESPACE), b"link") => {
+                            match read_alternate(&self.reader, &start)? {
+                                Some(alternate) => url.alternates.push(alternate),
+                                None => url
+                                    .extensions
+                                    .push(read_fragment(&mut self.reader, &start)?),
                             }
                         }
-                    }
-
-                    if start.name().as_ref() == b"url" {
-                        continue;
-                    }
+                        (Some(IMAGE_NAMESPACE), b"image") => {
+                            url.images.push(read_image(&mut self.reader)?)
+                        }
+                        (Some(VIDEO_NAMESPACE), b"video") => {
+                            url.videos.push(read_video(&mut self.reader)?)
+                        }
+                        (Some(NEWS_NAMESPACE), b"news") => {
+                            url.news = Some(read_news(&mut self.reader)?)
+                        }
+                        _ => match start.name().as_ref() {
+                            b"url" => self.in_entry = true,
+                            name @ (b"loc" | b"lastmod" | b"priority" | b"changefreq") => {
+                                let text =
+                                    read_text_content(&mut self.reader, &mut self.nested_buf)?;
+                                if text.is_empty() {
+                                    continue;
+                                }
 
-                    loop {
-                        match reader.read_event_into(&mut nested_buf) {
-                            Err(e) => {
-                                panic!("Error at position {}: {:?}", reader.buffer_position(), e)
-                            }
-                            Ok(Event::Text(e)) => {
-                                let text = e.unescape()?.to_string();
-                                match start.name().as_ref() {
+                                match name {
                                     b"loc" => url.loc.push_str(&text),
                                     b"lastmod" => url.last_mod = Some(W3CDateTime::new(&text)?),
                                     b"priority" => {
-                                        url.priority = Some(Priority::new(text.parse()?)?)
-                                    }
-                                    b"changefreq" => {
-                                        url.change_freq = Some(text.to_string().into())
+                                        url.priority = Some(Priority::parse_unchecked(&text)?)
                                     }
-                                    _ => {}
+                                    _ => url.change_freq = Some(text.parse()?),
                                 }
                             }
-                            _ => break,
-                        }
+                            _ => {
+                                let fragment = read_fragment(&mut self.reader, &start)?;
+                                if self.in_entry {
+                                    url.extensions.push(fragment);
+                                }
+                            }
+                        },
                     }
                 }
-                Ok(Event::End(e)) => {
-                    if e.name().as_ref() == b"url" {
-                        sitemap.entries.push(url);
-                        url = UrlEntry::new();
-                    }
+                Event::End(e) if e.name().as_ref() == b"url" => {
+                    self.in_entry = false;
+                    self.entry_count += 1;
+
+                    return Ok(Some(url));
                 }
                 _ => {}
             }
-            buf.clear();
         }
-        Ok(sitemap)
     }
-    fn write<W: Write>(&self, mut writer: Writer<W>) -> Result<W, Error> {
-        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
-
-        let name = "urlset";
-        let mut element = BytesStart::new(name);
-        if let Some(ref schema_instance) = self.schema_instance {
-            element.push_attribute(("xmlns:xsi", schema_instance.as_str()));
-        }
-        if let Some(ref schema_location) = self.schema_location {
-            element.push_attribute(("xsi:schemaLocation", schema_location.as_str()));
-        }
-        let namespace = if self.namespace.is_empty() {
-            NAMESPACE
-        } else {
-            self.namespace.as_str()
-        };
-        element.push_attribute(("xmlns", namespace));
-        writer.write_event(Event::Start(element))?;
-
-        for url_entry in &self.entries {
-            let inner_name = "url";
-            writer.write_event(Event::Start(BytesStart::new(inner_name)))?;
+}
 
-            Self::write_text_element(&mut writer, "loc", url_entry.loc.clone())?;
+impl<R: BufRead> Iterator for SitemapReader<R> {
+    type Item = Result<UrlEntry, Error>;
 
-            if let Some(lastmod) = url_entry.last_mod {
-                Self::write_text_element(&mut writer, "lastmod", lastmod.to_string())?;
-            }
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
 
-            if let Some(changefreq) = url_entry.change_freq {
-                Self::write_text_element(&mut writer, "changefreq", changefreq.to_string())?;
+        match self.read_entry() {
+            Ok(Some(entry)) => Some(Ok(entry)),
+            Ok(None) => {
+                self.done = true;
+                None
             }
-
-            if let Some(priority) = url_entry.priority {
-                Self::write_text_element(&mut writer, "priority", priority.to_string())?;
+            Err(e) => {
+                self.done = true;
+                let entry = if self.in_entry {
+                    Some(self.entry_count)
+                } else {
+                    None
+                };
+                Some(Err(with_position(&self.reader, entry, e)))
             }
-
-            writer.write_event(Event::End(BytesEnd::new(inner_name)))?;
         }
-
-        writer.write_event(Event::End(BytesEnd::new(name)))?;
-        Ok(writer.into_inner())
     }
 }
 
@@ -181,12 +488,24 @@ impl Entries for Sitemap {
 
 /// The priority of this URL relative to other URLs on the site.
 /// Valid values range from 0.0 to 1.0.
-#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
-pub struct Priority(pub f32);
+///
+/// A Priority is a decimal that keeps the number of decimal places it was given
+/// with, so that `0.50` is written back as `0.50`. Priorities are compared by value,
+/// so `0.5` and `0.50` are equal. It is serialized as the string it is written as.
+///
+/// Priorities read from a sitemap may be out of range, so that
+/// [`Sitemap::validate`] can report them along with every other problem.
+#[derive(Debug, Clone, Copy)]
+pub struct Priority {
+    /// The value in units of `10^-scale`, so that `0.85` is 85 with a scale of 2.
+    units: i64,
+    /// The number of decimal places, at most [`Priority::MAX_SCALE`].
+    scale: u8,
+}
 
 /// A URL entry. It is a parent XML tag containing the required `<loc>` element
 /// and the three optional `<lastmod>`, `<changrefreq>`, and `<priority>` elements.
-#[derive(Debug, Default, PartialEq, Clone, Serialize)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
 pub struct UrlEntry {
     /// The URL of the described page. It is required.
     pub loc: String,
@@ -196,6 +515,21 @@ pub struct UrlEntry {
     pub change_freq: Option<ChangeFreq>,
     /// Optional. The priority of this URL relative to other URLs on the site.
     pub priority: Option<Priority>,
+    /// Optional. Localized versions of the page, from `<xhtml:link>` elements.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<Alternate>,
+    /// Optional. Images on the page, from the image sitemap extension.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<Image>,
+    /// Optional. Videos on the page, from the video sitemap extension.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub videos: Vec<Video>,
+    /// Optional. The news article on the page, from the news sitemap extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub news: Option<News>,
+    /// Optional. Any other child elements of the `<url>`, as XML fragments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<Extension>,
 }
 
 impl UrlEntry {
@@ -206,32 +540,290 @@ impl UrlEntry {
             last_mod: None,
             change_freq: None,
             priority: None,
+            alternates: vec![],
+            images: vec![],
+            videos: vec![],
+            news: None,
+            extensions: vec![],
+        }
+    }
+
+    /// Write the entry as a `<url>` element of the document whose root start tag is `root`.
+    pub(crate) fn write<W: Write>(
+        &self,
+        writer: &mut Writer<W>,
+        root: &BytesStart,
+    ) -> Result<(), Error> {
+        let name = "url";
+        writer.write_event(Event::Start(BytesStart::new(name)))?;
+
+        Sitemap::write_text_element(writer, "loc", &self.loc)?;
+
+        if let Some(lastmod) = self.last_mod {
+            Sitemap::write_text_element(writer, "lastmod", lastmod.to_string())?;
+        }
+
+        if let Some(changefreq) = self.change_freq {
+            Sitemap::write_text_element(writer, "changefreq", changefreq.to_string())?;
+        }
+
+        if let Some(priority) = self.priority {
+            Sitemap::write_text_element(writer, "priority", priority.to_string())?;
+        }
+
+        for alternate in &self.alternates {
+            write_alternate(writer, root, alternate)?;
+        }
+
+        for image in &self.images {
+            write_image(writer, root, image)?;
+        }
+
+        for video in &self.videos {
+            write_video(writer, root, video)?;
+        }
+
+        if let Some(ref news) = self.news {
+            write_news(writer, root, news)?;
+        }
+
+        for extension in &self.extensions {
+            write_fragment(writer, root, extension)?;
         }
+
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
+
+    /// Validate the entry's images: there must be no more than 1,000 of them, and
+    /// each must have a valid `<image:loc>`.
+    pub fn validate_images(&self) -> Result<(), Error> {
+        if self.images.len() > MAX_IMAGES {
+            return Err(Error::TooManyImages);
+        }
+
+        for image in &self.images {
+            image.validate_loc()?;
+        }
+
+        Ok(())
+    }
+
+    /// Validate each of the entry's videos.
+    pub fn validate_videos(&self) -> Result<(), Error> {
+        for video in &self.videos {
+            video.validate()?;
+        }
+
+        Ok(())
     }
 }
 
 impl Priority {
-    /// Create a new, valid Priority.
+    /// The most decimal places a Priority can have.
+    pub const MAX_SCALE: u8 = 9;
+
+    /// Create a new Priority, validating that it falls within the 0.0 to 1.0 range.
+    /// It has as many decimal places as needed to represent `priority`, and at
+    /// least one.
     pub fn new(priority: f32) -> Result<Self, Error> {
-        Ok(Self(priority))
+        if priority < 0.0 {
+            return Err(Error::PriorityTooLow);
+        }
+        if priority > 1.0 {
+            return Err(Error::PriorityTooHigh);
+        }
+
+        Self::from_f32_unchecked(priority)
+    }
+
+    /// Convert an f32 to a Priority without checking its range.
+    fn from_f32_unchecked(priority: f32) -> Result<Self, Error> {
+        if !priority.is_finite() {
+            return Err(Error::InvalidPriority(priority.to_string()));
+        }
+
+        // Debug formatting gives the shortest text that reads back as the same f32,
+        // except that tiny values are written with an exponent.
+        let text = format!("{:?}", priority);
+        if text.contains('e') {
+            let text = format!("{:.*}", usize::from(Self::MAX_SCALE), priority);
+            let text = text.trim_end_matches('0');
+            let text = format!("{}{}", text, if text.ends_with('.') { "0" } else { "" });
+            return Self::parse_unchecked(&text);
+        }
+        Self::parse_unchecked(&text)
     }
 
-    /// Validate that `<priority>` falls within the 0.0 to 1.0 range.
+    /// Parse a decimal such as `0.8` or `0.85` without checking its range, as a
+    /// `<priority>` is read. Digits beyond [`Priority::MAX_SCALE`] decimal places
+    /// are dropped if they are zeros and rounded otherwise.
+    pub(crate) fn parse_unchecked(value: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidPriority(value.to_string());
+        let text = value.trim();
+        let (negative, text) = match text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, text),
+        };
+        let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !is_digits(integer)
+            || !is_digits(fraction)
+        {
+            return Err(invalid());
+        }
+
+        let max_scale = usize::from(Self::MAX_SCALE);
+        let (fraction, round_up) = if fraction.len() > max_scale {
+            (
+                &fraction[..max_scale],
+                fraction.as_bytes()[max_scale] >= b'5',
+            )
+        } else {
+            (fraction, false)
+        };
+
+        let digits = format!("{}{}", integer.trim_start_matches('0'), fraction);
+        let units = if digits.is_empty() {
+            0
+        } else {
+            digits.parse::<i64>().map_err(|_| invalid())?
+        };
+        let units = units.checked_add(i64::from(round_up)).ok_or_else(invalid)?;
+
+        Ok(Priority {
+            units: if negative { -units } else { units },
+            scale: fraction.len() as u8,
+        })
+    }
+
+    /// Validate that the priority falls within the 0.0 to 1.0 range.
     pub fn validate(&self) -> Result<Self, Error> {
-        if self.0 < 0.0 {
+        if self.units < 0 {
             return Err(Error::PriorityTooLow);
         }
-
-        if self.0 > 1.0 {
+        if self.normalized() > i128::from(10_u64.pow(u32::from(Self::MAX_SCALE))) {
             return Err(Error::PriorityTooHigh);
         }
 
-        Ok(self.to_owned())
+        Ok(*self)
+    }
+
+    /// The number of decimal places the priority was given with.
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// The nearest f32 to the priority's value.
+    pub fn as_f32(&self) -> f32 {
+        self.units as f32 / 10_u32.pow(u32::from(self.scale)) as f32
+    }
+
+    /// The value in units of `10^-MAX_SCALE`, for exact comparison.
+    fn normalized(&self) -> i128 {
+        i128::from(self.units) * i128::from(10_u64.pow(u32::from(Self::MAX_SCALE - self.scale)))
+    }
+}
+
+impl FromStr for Priority {
+    type Err = Error;
+
+    /// Parse a decimal such as `0.8` or `0.85`, validating that it falls within the
+    /// 0.0 to 1.0 range.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse_unchecked(value)?.validate()
+    }
+}
+
+impl TryFrom<&str> for Priority {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<f32> for Priority {
+    type Error = Error;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Priority::new(value)
+    }
+}
+
+impl TryFrom<f64> for Priority {
+    type Error = Error;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Priority::new(value as f32)
+    }
+}
+
+impl PartialEq for Priority {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl Eq for Priority {}
+
+impl Ord for Priority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.normalized().cmp(&other.normalized())
+    }
+}
+
+impl PartialOrd for Priority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Serialize for Priority {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A Priority is read from a string, or from a number as written by earlier versions.
+/// Like a `<priority>`, it is not checked to be in range.
+impl<'de> Deserialize<'de> for Priority {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PriorityVisitor;
+
+        impl<'de> de::Visitor<'de> for PriorityVisitor {
+            type Value = Priority;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a decimal priority")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Priority, E> {
+                Priority::parse_unchecked(value).map_err(E::custom)
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Priority, E> {
+                Priority::from_f32_unchecked(value as f32).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Priority, E> {
+                Priority::parse_unchecked(&value.to_string()).map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Priority, E> {
+                Priority::parse_unchecked(&value.to_string()).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(PriorityVisitor)
     }
 }
 
 /// ChangeFreq represents how frequently the page is likely to change.
-#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+/// It is serialized as its `<changefreq>` value.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
 pub enum ChangeFreq {
     Always,
     Hourly,
@@ -243,28 +835,47 @@ pub enum ChangeFreq {
 }
 
 impl ChangeFreq {
-    pub fn new(string: String) -> Self {
-        Self::from(string)
+    /// Create a new ChangeFreq from a case-insensitive `<changefreq>` value.
+    pub fn new(string: String) -> Result<Self, Error> {
+        Self::try_from(string)
     }
 }
 
-impl From<String> for ChangeFreq {
-    fn from(value: String) -> Self {
+impl FromStr for ChangeFreq {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         let normalized_value = value.to_lowercase();
 
         match normalized_value.as_ref() {
-            "always" => ChangeFreq::Always,
-            "hourly" => ChangeFreq::Hourly,
-            "daily" => ChangeFreq::Daily,
-            "weekly" => ChangeFreq::Weekly,
-            "monthly" => ChangeFreq::Monthly,
-            "yearly" => ChangeFreq::Yearly,
-            "never" => ChangeFreq::Never,
-            _ => panic!("Unrecognized change frequency"),
+            "always" => Ok(ChangeFreq::Always),
+            "hourly" => Ok(ChangeFreq::Hourly),
+            "daily" => Ok(ChangeFreq::Daily),
+            "weekly" => Ok(ChangeFreq::Weekly),
+            "monthly" => Ok(ChangeFreq::Monthly),
+            "yearly" => Ok(ChangeFreq::Yearly),
+            "never" => Ok(ChangeFreq::Never),
+            _ => Err(Error::InvalidChangeFreq(value.to_string())),
         }
     }
 }
 
+impl TryFrom<&str> for ChangeFreq {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for ChangeFreq {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
 impl fmt::Display for ChangeFreq {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let data = match *self {
@@ -283,6 +894,20 @@ impl fmt::Display for ChangeFreq {
 
 impl fmt::Display for Priority {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(&format!("{:.1}", &self.0))
+        let sign = if self.units < 0 { "-" } else { "" };
+        let units = self.units.unsigned_abs();
+        if self.scale == 0 {
+            return write!(f, "{}{}", sign, units);
+        }
+
+        let divisor = 10_u64.pow(u32::from(self.scale));
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            units / divisor,
+            units % divisor,
+            width = usize::from(self.scale)
+        )
     }
 }
@@ -0,0 +1,398 @@
+//! Whole-document validation of sitemaps and sitemap indexes.
+//!
+//! Unlike the `validate_*` methods on individual values, which stop at the first
+//! problem, [`Sitemap::validate`], [`SiteIndex::validate`] and
+//! [`SitemapsFile::validate`] check the whole document and collect every problem
+//! found into a [`ValidationReport`].
+//!
+//! The hreflang alternates of a sitemap's entries are checked against each other by
+//! `validate_alternates`.
+//!
+//! The [location scoping rules](https://sitemaps.org/protocol.html#location) are
+//! checked separately by `validate_scope`, as they depend on where the document is
+//! hosted, and the rules of a robots.txt file by `validate_robots`.
+
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+use url::Url;
+
+use crate::error::Error;
+use crate::news::MAX_NEWS_URLS;
+use crate::robots::Robots;
+use crate::siteindex::SiteIndex;
+use crate::sitemap::{Sitemap, UrlEntry};
+use crate::{normalize_loc, validate_url, SitemapsFile, MAX_ENTRIES, NAMESPACE};
+
+/// How serious a validation problem is.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Severity {
+    /// The document is accepted by search engines, but probably not as intended.
+    Warning,
+    /// The document, or the entry, breaks the protocol and may be rejected.
+    Error,
+}
+
+/// A single problem found in a document.
+#[derive(Debug)]
+pub struct Issue {
+    pub severity: Severity,
+    /// The index of the entry the problem is in, or `None` if it concerns the whole document.
+    pub entry: Option<usize>,
+    pub error: Error,
+}
+
+/// The problems found by validating a document, in document order.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub issues: Vec<Issue>,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match *self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        f.write_str(data)
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.entry {
+            Some(entry) => write!(f, "{}: entry {}: {}", self.severity, entry, self.error),
+            None => write!(f, "{}: {}", self.severity, self.error),
+        }
+    }
+}
+
+impl ValidationReport {
+    /// Whether no problems of [`Severity::Error`] were found. Warnings are allowed.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// The problems of [`Severity::Error`].
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// The problems of [`Severity::Warning`].
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn push(&mut self, severity: Severity, entry: Option<usize>, error: Error) {
+        self.issues.push(Issue {
+            severity,
+            entry,
+            error,
+        });
+    }
+
+    /// Check the number of entries and the root element's namespace, which is empty
+    /// if the document declared none.
+    fn check_document(&mut self, entry_count: usize, namespace: &str) {
+        if entry_count > MAX_ENTRIES {
+            self.push(Severity::Error, None, Error::TooManyUrls);
+        }
+
+        if namespace != NAMESPACE {
+            self.push(
+                Severity::Warning,
+                None,
+                Error::NonStandardNamespace(namespace.to_string()),
+            );
+        }
+    }
+
+    /// Check that each `<loc>` is a valid URL that hasn't already been listed, comparing
+    /// normalized URLs as merging and diffing do.
+    fn check_locs<'a, I: Iterator<Item = &'a str>>(&mut self, locs: I) {
+        let mut seen = HashSet::new();
+
+        for (entry, loc) in locs.enumerate() {
+            if let Err(e) = validate_url(loc) {
+                self.push(Severity::Error, Some(entry), e);
+            } else if !seen.insert(normalize_loc(loc)) {
+                self.push(
+                    Severity::Warning,
+                    Some(entry),
+                    Error::DuplicateLoc(loc.to_string()),
+                );
+            }
+        }
+    }
+}
+
+/// The URLs a sitemap may list: those on the same scheme, host and port as the
+/// sitemap and in its directory or below. A sitemap at
+/// `http://example.com/catalog/sitemap.xml` may list `http://example.com/catalog/shoes`
+/// but not `http://example.com/about` or `https://example.com/catalog/shoes`.
+///
+/// URLs on other hosts are allowed if those hosts are [added](Scope::allow_host),
+/// such as when their robots.txt files point to the sitemap, permitting cross-submission.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    directory: Url,
+    allowed_hosts: Vec<String>,
+}
+
+impl Scope {
+    /// Create the scope of a sitemap hosted at `sitemap_url`. For a sitemap that
+    /// hasn't been published yet, pass the directory URL with a trailing `/`.
+    pub fn new(sitemap_url: &str) -> Result<Self, Error> {
+        Ok(Self {
+            directory: Url::parse(sitemap_url)?.join(".")?,
+            allowed_hosts: vec![],
+        })
+    }
+
+    /// Permit any URL on `host`, whose robots.txt file references the sitemap.
+    pub fn allow_host<H: Into<String>>(mut self, host: H) -> Self {
+        self.allowed_hosts.push(host.into());
+        self
+    }
+
+    /// Whether a URL is within the scope. URLs that can't be parsed are not.
+    pub fn contains(&self, loc: &str) -> bool {
+        let url = match Url::parse(loc) {
+            Ok(url) => url,
+            Err(_) => return false,
+        };
+
+        if let Some(host) = url.host_str() {
+            if self
+                .allowed_hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(host))
+            {
+                return true;
+            }
+        }
+
+        url.scheme() == self.directory.scheme()
+            && url.host() == self.directory.host()
+            && url.port_or_known_default() == self.directory.port_or_known_default()
+            && url.path().starts_with(self.directory.path())
+    }
+
+    /// Report every valid URL outside the scope. Invalid URLs are left to `validate`.
+    fn check_locs<'a, I: Iterator<Item = &'a str>>(&self, locs: I) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        for (entry, loc) in locs.enumerate() {
+            if Url::parse(loc).is_ok() && !self.contains(loc) {
+                report.push(
+                    Severity::Error,
+                    Some(entry),
+                    Error::OutOfScope(loc.to_string()),
+                );
+            }
+        }
+
+        report
+    }
+}
+
+/// The alternates of an entry, with hreflang values compared case insensitively.
+fn alternate_set(url: &UrlEntry) -> HashSet<(String, &str)> {
+    url.alternates
+        .iter()
+        .map(|a| (a.hreflang.to_ascii_lowercase(), a.href.as_str()))
+        .collect()
+}
+
+/// Report every URL that robots.txt disallows `user_agent` from crawling.
+fn check_robots<'a, I: Iterator<Item = &'a str>>(
+    robots: &Robots,
+    user_agent: &str,
+    locs: I,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for (entry, loc) in locs.enumerate() {
+        if !robots.is_allowed(user_agent, loc) {
+            report.push(
+                Severity::Error,
+                Some(entry),
+                Error::DisallowedByRobots(loc.to_string()),
+            );
+        }
+    }
+
+    report
+}
+
+impl Sitemap {
+    /// Check the whole sitemap and report every problem found: invalid or too long
+    /// URLs, out of range priorities, too many entries, images or news articles,
+    /// invalid videos and hreflang values, duplicate URLs and a missing or non-standard namespace.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        report.check_document(self.entries.len(), &self.namespace);
+        report.check_locs(self.entries.iter().map(|url| url.loc.as_str()));
+
+        let news_count = self.entries.iter().filter(|url| url.news.is_some()).count();
+        if news_count > MAX_NEWS_URLS {
+            report.push(Severity::Error, None, Error::TooManyNewsUrls);
+        }
+
+        for (entry, url) in self.entries.iter().enumerate() {
+            let entry = Some(entry);
+
+            if let Some(priority) = url.priority {
+                if let Err(e) = priority.validate() {
+                    report.push(Severity::Error, entry, e);
+                }
+            }
+            if let Err(e) = url.validate_images() {
+                report.push(Severity::Error, entry, e);
+            }
+            for video in &url.videos {
+                if let Err(e) = video.validate() {
+                    report.push(Severity::Error, entry, e);
+                }
+            }
+            for alternate in &url.alternates {
+                if let Err(e) = alternate.validate_hreflang() {
+                    report.push(Severity::Error, entry, e);
+                }
+            }
+        }
+
+        report.issues.sort_by_key(|issue| issue.entry);
+        report
+    }
+
+    /// Report every entry whose `<loc>` is outside the scope of the sitemap.
+    pub fn validate_scope(&self, scope: &Scope) -> ValidationReport {
+        scope.check_locs(self.entries.iter().map(|url| url.loc.as_str()))
+    }
+
+    /// Report every problem with the hreflang alternates of the entries: invalid
+    /// hreflang values, entries with alternates that don't list themselves, and
+    /// alternates that are entries of this sitemap but don't link back or list a
+    /// different set of alternates, as every URL in a cluster must list the same
+    /// ones. Alternates pointing outside this sitemap cannot be checked.
+    pub fn validate_alternates(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let by_loc = self
+            .entries
+            .iter()
+            .map(|url| (url.loc.as_str(), url))
+            .collect::<HashMap<&str, &UrlEntry>>();
+
+        for (entry, url) in self.entries.iter().enumerate() {
+            if url.alternates.is_empty() {
+                continue;
+            }
+            let entry = Some(entry);
+
+            for alternate in &url.alternates {
+                if let Err(e) = alternate.validate_hreflang() {
+                    report.push(Severity::Error, entry, e);
+                }
+            }
+
+            if !url.alternates.iter().any(|a| a.href == url.loc) {
+                report.push(Severity::Error, entry, Error::MissingSelfAlternate);
+            }
+
+            let cluster = alternate_set(url);
+            let mut checked = HashSet::new();
+            for alternate in &url.alternates {
+                let other = match by_loc.get(alternate.href.as_str()) {
+                    Some(other) if other.loc != url.loc && checked.insert(&other.loc) => other,
+                    _ => continue,
+                };
+
+                if !other.alternates.iter().any(|a| a.href == url.loc) {
+                    report.push(
+                        Severity::Error,
+                        entry,
+                        Error::MissingReturnLink(other.loc.clone()),
+                    );
+                } else if alternate_set(other) != cluster {
+                    report.push(
+                        Severity::Error,
+                        entry,
+                        Error::InconsistentAlternates(other.loc.clone()),
+                    );
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Report every entry whose `<loc>` robots.txt disallows `user_agent` from
+    /// crawling. The rules are applied to every URL, so `robots` should be the
+    /// robots.txt of the host the URLs are on.
+    pub fn validate_robots(&self, robots: &Robots, user_agent: &str) -> ValidationReport {
+        check_robots(
+            robots,
+            user_agent,
+            self.entries.iter().map(|url| url.loc.as_str()),
+        )
+    }
+}
+
+impl SiteIndex {
+    /// Check the whole sitemap index and report every problem found: invalid or too
+    /// long URLs, too many entries, duplicate URLs and a missing or non-standard namespace.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        report.check_document(self.entries.len(), &self.namespace);
+        report.check_locs(self.entries.iter().map(|entry| entry.loc.as_str()));
+
+        report
+    }
+
+    /// Report every entry whose `<loc>` is outside the scope of the sitemap index.
+    pub fn validate_scope(&self, scope: &Scope) -> ValidationReport {
+        scope.check_locs(self.entries.iter().map(|entry| entry.loc.as_str()))
+    }
+
+    /// Report every sitemap that robots.txt disallows `user_agent` from crawling.
+    pub fn validate_robots(&self, robots: &Robots, user_agent: &str) -> ValidationReport {
+        check_robots(
+            robots,
+            user_agent,
+            self.entries.iter().map(|entry| entry.loc.as_str()),
+        )
+    }
+}
+
+impl SitemapsFile {
+    /// Check the whole sitemap or sitemap index and report every problem found.
+    pub fn validate(&self) -> ValidationReport {
+        match self {
+            SitemapsFile::Sitemap(sitemap) => sitemap.validate(),
+            SitemapsFile::SiteIndex(index) => index.validate(),
+        }
+    }
+
+    /// Report every entry whose `<loc>` is outside the scope of the sitemap or
+    /// sitemap index. For a file read from disk, create the scope from the URL it
+    /// will be published at, or a base URL.
+    pub fn validate_scope(&self, scope: &Scope) -> ValidationReport {
+        match self {
+            SitemapsFile::Sitemap(sitemap) => sitemap.validate_scope(scope),
+            SitemapsFile::SiteIndex(index) => index.validate_scope(scope),
+        }
+    }
+
+    /// Report every entry whose `<loc>` robots.txt disallows `user_agent` from crawling.
+    pub fn validate_robots(&self, robots: &Robots, user_agent: &str) -> ValidationReport {
+        match self {
+            SitemapsFile::Sitemap(sitemap) => sitemap.validate_robots(robots, user_agent),
+            SitemapsFile::SiteIndex(index) => index.validate_robots(robots, user_agent),
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! The [Google video sitemap extension](https://developers.google.com/search/docs/crawling-indexing/sitemaps/video-sitemaps),
+//! which adds `<video:video>` elements to a `<url>`.
+
+use core::fmt;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+use crate::error::Error;
+use crate::sitemap::Sitemap;
+use crate::w3c_datetime::W3CDateTime;
+use crate::{
+    namespace_prefix, read_fragment, read_text_content, resolve_element, write_fragment, Extension,
+    Sitemaps, XmlReader,
+};
+
+/// The namespace of the video sitemap extension, bound to the `video` prefix.
+pub const VIDEO_NAMESPACE: &str = "http://www.google.com/schemas/sitemap-video/1.1";
+
+/// The minimum video duration in seconds.
+pub const MIN_DURATION: u32 = 1;
+/// The maximum video duration in seconds.
+pub const MAX_DURATION: u32 = 28_800;
+
+/// The local names of the children of `<video:video>` that are read into a [`Video`].
+const ELEMENTS: &[&[u8]] = &[
+    b"thumbnail_loc",
+    b"title",
+    b"description",
+    b"content_loc",
+    b"player_loc",
+    b"duration",
+    b"expiration_date",
+    b"rating",
+    b"view_count",
+    b"publication_date",
+    b"family_friendly",
+    b"restriction",
+    b"platform",
+    b"requires_subscription",
+    b"uploader",
+    b"live",
+    b"tag",
+];
+
+/// A video on the page described by a URL entry. It is a parent XML tag containing
+/// the required `<video:thumbnail_loc>`, `<video:title>` and `<video:description>`
+/// elements, at least one of `<video:content_loc>` and `<video:player_loc>`, and
+/// a number of optional elements.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Video {
+    /// The URL of the video's thumbnail image. It is required.
+    pub thumbnail_loc: String,
+    /// The title of the video. It is required.
+    pub title: String,
+    /// A description of the video. It is required.
+    pub description: String,
+    /// The URL of the video file itself.
+    pub content_loc: Option<String>,
+    /// The URL of a player for the video.
+    pub player_loc: Option<String>,
+    /// Optional. The duration of the video in seconds.
+    pub duration: Option<u32>,
+    /// Optional. The date after which the video is no longer available.
+    pub expiration_date: Option<W3CDateTime>,
+    /// Optional. The rating of the video, from 0.0 to 5.0.
+    pub rating: Option<f32>,
+    /// Optional. The number of times the video has been viewed.
+    pub view_count: Option<u64>,
+    /// Optional. The date the video was first published.
+    pub publication_date: Option<W3CDateTime>,
+    /// Optional. Whether the video is suitable for viewing by children.
+    pub family_friendly: Option<bool>,
+    /// Optional. The countries where the video may or may not be played.
+    pub restriction: Option<Restriction>,
+    /// Optional. The platforms on which the video may or may not be played.
+    pub platform: Option<Restriction>,
+    /// Optional. Whether a subscription is required to view the video.
+    pub requires_subscription: Option<bool>,
+    /// Optional. The video's uploader.
+    pub uploader: Option<Uploader>,
+    /// Optional. Whether the video is a live stream.
+    pub live: Option<bool>,
+    /// Optional. Tags describing the video.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Optional. Any other child elements of the `<video:video>`, such as
+    /// `<video:price>`, as XML fragments.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<Extension>,
+}
+
+/// Whether a restriction lists the values that are allowed or those that are denied.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Relationship {
+    Allow,
+    Deny,
+}
+
+/// A space-separated list of country codes (`<video:restriction>`) or platforms
+/// (`<video:platform>`) that a video is either allowed or denied on.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Restriction {
+    pub relationship: Relationship,
+    pub values: Vec<String>,
+}
+
+/// The uploader of a video, with an optional URL to more information about them.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Uploader {
+    pub name: String,
+    pub info: Option<String>,
+}
+
+impl Video {
+    /// Create a new, empty Video.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate that the required elements are present and that the duration and
+    /// rating fall within their allowed ranges.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.thumbnail_loc.is_empty() {
+            return Err(Error::MissingVideoField("thumbnail_loc"));
+        }
+        if self.title.is_empty() {
+            return Err(Error::MissingVideoField("title"));
+        }
+        if self.description.is_empty() {
+            return Err(Error::MissingVideoField("description"));
+        }
+        if self.content_loc.is_none() && self.player_loc.is_none() {
+            return Err(Error::MissingVideoField("content_loc"));
+        }
+
+        if let Some(duration) = self.duration {
+            if !(MIN_DURATION..=MAX_DURATION).contains(&duration) {
+                return Err(Error::VideoDurationOutOfRange(duration));
+            }
+        }
+
+        if let Some(rating) = self.rating {
+            if !(0.0..=5.0).contains(&rating) {
+                return Err(Error::VideoRatingOutOfRange(rating));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Relationship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match *self {
+            Relationship::Allow => "allow",
+            Relationship::Deny => "deny",
+        };
+
+        f.write_str(data)
+    }
+}
+
+impl Restriction {
+    fn parse(field: &'static str, relationship: Option<String>, text: &str) -> Result<Self, Error> {
+        let relationship = match relationship.as_deref() {
+            Some("allow") => Relationship::Allow,
+            Some("deny") => Relationship::Deny,
+            _ => {
+                return Err(Error::InvalidVideoValue {
+                    field,
+                    value: relationship.unwrap_or_default(),
+                })
+            }
+        };
+
+        Ok(Self {
+            relationship,
+            values: text.split_whitespace().map(String::from).collect(),
+        })
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(field: &'static str, text: &str) -> Result<T, Error> {
+    text.parse().map_err(|_| Error::InvalidVideoValue {
+        field,
+        value: text.to_string(),
+    })
+}
+
+fn parse_yes_no(field: &'static str, text: &str) -> Result<bool, Error> {
+    match text.to_lowercase().as_ref() {
+        "yes" => Ok(true),
+        "no" => Ok(false),
+        _ => Err(Error::InvalidVideoValue {
+            field,
+            value: text.to_string(),
+        }),
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Read the children of a `<video:video>` element, up to and including its end tag.
+pub(crate) fn read_video<R: BufRead>(reader: &mut XmlReader<R>) -> Result<Video, Error> {
+    let mut video = Video::new();
+    let mut buf = Vec::new();
+    let mut text_buf = Vec::new();
+
+    loop {
+        buf.clear();
+        match reader.read_event_into(&mut buf)? {
+            Event::Eof => return Err(Error::UnexpectedEof),
+            Event::Start(start) => {
+                let (namespace, local_name) =
+                    resolve_element(reader, start.name(), &[VIDEO_NAMESPACE]);
+                if namespace.is_none() || !ELEMENTS.contains(&local_name.as_ref()) {
+                    video.extensions.push(read_fragment(reader, &start)?);
+                    continue;
+                }
+
+                let mut relationship = None;
+                let mut info = None;
+                for attr_result in start.attributes() {
+                    let a = attr_result?;
+                    match a.key.as_ref() {
+                        b"relationship" => {
+                            relationship = Some(a.decode_and_unescape_value(reader)?.to_string())
+                        }
+                        b"info" => info = Some(a.decode_and_unescape_value(reader)?.to_string()),
+                        _ => {}
+                    }
+                }
+
+                let text = read_text_content(reader, &mut text_buf)?;
+                let text = text.trim();
+                match (namespace, local_name.as_ref()) {
+                    (Some(_), b"thumbnail_loc") => video.thumbnail_loc.push_str(text),
+                    (Some(_), b"title") => video.title.push_str(text),
+                    (Some(_), b"description") => video.description.push_str(text),
+                    (Some(_), b"content_loc") => video.content_loc = Some(text.to_string()),
+                    (Some(_), b"player_loc") => video.player_loc = Some(text.to_string()),
+                    (Some(_), b"duration") => video.duration = Some(parse_value("duration", text)?),
+                    (Some(_), b"expiration_date") => {
+                        video.expiration_date = Some(W3CDateTime::new(text)?)
+                    }
+                    (Some(_), b"rating") => video.rating = Some(parse_value("rating", text)?),
+                    (Some(_), b"view_count") => {
+                        video.view_count = Some(parse_value("view_count", text)?)
+                    }
+                    (Some(_), b"publication_date") => {
+                        video.publication_date = Some(W3CDateTime::new(text)?)
+                    }
+                    (Some(_), b"family_friendly") => {
+                        video.family_friendly = Some(parse_yes_no("family_friendly", text)?)
+                    }
+                    (Some(_), b"restriction") => {
+                        video.restriction =
+                            Some(Restriction::parse("restriction", relationship, text)?)
+                    }
+                    (Some(_), b"platform") => {
+                        video.platform = Some(Restriction::parse("platform", relationship, text)?)
+                    }
+                    (Some(_), b"requires_subscription") => {
+                        video.requires_subscription =
+                            Some(parse_yes_no("requires_subscription", text)?)
+                    }
+                    (Some(_), b"uploader") => {
+                        video.uploader = Some(Uploader {
+                            name: text.to_string(),
+                            info,
+                        })
+                    }
+                    (Some(_), b"live") => video.live = Some(parse_yes_no("live", text)?),
+                    (Some(_), b"tag") => video.tags.push(text.to_string()),
+                    _ => {}
+                }
+            }
+            Event::End(end)
+                if matches!(
+                    resolve_element(reader, end.name(), &[VIDEO_NAMESPACE]),
+                    (Some(_), name) if name.as_ref() == b"video"
+                ) =>
+            {
+                return Ok(video)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Write a `<video:video>` element of the document whose root start tag is `root`,
+/// with the prefix `root` binds to the video namespace.
+pub(crate) fn write_video<W: Write>(
+    writer: &mut Writer<W>,
+    root: &BytesStart,
+    video: &Video,
+) -> Result<(), Error> {
+    let prefix = namespace_prefix(root, VIDEO_NAMESPACE, "video");
+    let qualified = |local_name: &str| format!("{}:{}", prefix, local_name);
+    let name = qualified("video");
+    writer.write_event(Event::Start(BytesStart::new(name.as_str())))?;
+
+    Sitemap::write_text_element(writer, qualified("thumbnail_loc"), &video.thumbnail_loc)?;
+    Sitemap::write_text_element(writer, qualified("title"), &video.title)?;
+    Sitemap::write_text_element(writer, qualified("description"), &video.description)?;
+
+    if let Some(ref content_loc) = video.content_loc {
+        Sitemap::write_text_element(writer, qualified("content_loc"), content_loc)?;
+    }
+    if let Some(ref player_loc) = video.player_loc {
+        Sitemap::write_text_element(writer, qualified("player_loc"), player_loc)?;
+    }
+    if let Some(duration) = video.duration {
+        Sitemap::write_text_element(writer, qualified("duration"), duration.to_string())?;
+    }
+    if let Some(expiration_date) = video.expiration_date {
+        Sitemap::write_text_element(
+            writer,
+            qualified("expiration_date"),
+            expiration_date.to_string(),
+        )?;
+    }
+    if let Some(rating) = video.rating {
+        Sitemap::write_text_element(writer, qualified("rating"), rating.to_string())?;
+    }
+    if let Some(view_count) = video.view_count {
+        Sitemap::write_text_element(writer, qualified("view_count"), view_count.to_string())?;
+    }
+    if let Some(publication_date) = video.publication_date {
+        Sitemap::write_text_element(
+            writer,
+            qualified("publication_date"),
+            publication_date.to_string(),
+        )?;
+    }
+    if let Some(family_friendly) = video.family_friendly {
+        Sitemap::write_text_element(
+            writer,
+            qualified("family_friendly"),
+            yes_no(family_friendly),
+        )?;
+    }
+    if let Some(ref restriction) = video.restriction {
+        write_restriction(writer, &qualified("restriction"), restriction)?;
+    }
+    if let Some(ref platform) = video.platform {
+        write_restriction(writer, &qualified("platform"), platform)?;
+    }
+    if let Some(requires_subscription) = video.requires_subscription {
+        Sitemap::write_text_element(
+            writer,
+            qualified("requires_subscription"),
+            yes_no(requires_subscription),
+        )?;
+    }
+    if let Some(ref uploader) = video.uploader {
+        let uploader_name = qualified("uploader");
+        let mut element = writer.create_element(&uploader_name);
+        if let Some(ref info) = uploader.info {
+            element = element.with_attribute(("info", info.as_str()));
+        }
+        element.write_text_content(BytesText::new(&uploader.name))?;
+    }
+    if let Some(live) = video.live {
+        Sitemap::write_text_element(writer, qualified("live"), yes_no(live))?;
+    }
+    for tag in &video.tags {
+        Sitemap::write_text_element(writer, qualified("tag"), tag)?;
+    }
+    for extension in &video.extensions {
+        write_fragment(writer, root, extension)?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
+
+fn write_restriction<W: Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    restriction: &Restriction,
+) -> Result<(), Error> {
+    writer
+        .create_element(name)
+        .with_attribute((
+            "relationship",
+            restriction.relationship.to_string().as_str(),
+        ))
+        .write_text_content(BytesText::new(&restriction.values.join(" ")))?;
+
+    Ok(())
+}
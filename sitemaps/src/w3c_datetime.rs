@@ -1,64 +1,340 @@
-use chrono::{DateTime, FixedOffset, NaiveDate, ParseError};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, ParseError, Timelike, Utc};
+use serde::de::{self, Deserializer};
 use serde::ser::Serializer;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
 
-/// A W3CDateTime is an ISO-8601 date or an RFC-3339 datetime.
-#[derive(Debug, PartialEq, Clone, Copy)]
+use crate::error::Error;
+
+/// A W3CDateTime is a date or datetime in one of the granularities of the
+/// [W3C Datetime](https://www.w3.org/TR/NOTE-datetime) profile of ISO 8601.
+///
+/// A value covers a period of time, from a year down to a fraction of a second.
+/// Values are ordered by the UTC instant their period starts, with dates taken as
+/// starting at midnight UTC, so that `2024-01-01` sorts before `2024-01-01T12:00Z`.
+/// Values starting at the same instant are ordered coarsest first, so that `2024`
+/// sorts before `2024-01-01`, and then by UTC offset and time zone designator.
+#[derive(Debug, Clone, Copy)]
 pub enum W3CDateTime {
+    /// A year, "YYYY".
+    Year(i32),
+    /// A year and month, "YYYY-MM".
+    YearMonth(i32, u32),
+    /// A complete date, "YYYY-MM-DD".
     Date(NaiveDate),
-    DateTime(DateTime<FixedOffset>, bool, bool),
+    /// A complete date plus hours and minutes, "YYYY-MM-DDThh:mmTZD", and whether
+    /// a UTC time zone is written as "Z".
+    DateTimeMinutes(DateTime<FixedOffset>, bool),
+    /// A complete date plus hours, minutes and seconds, "YYYY-MM-DDThh:mm:ssTZD",
+    /// the number of digits in its decimal fraction of a second, if any, and
+    /// whether a UTC time zone is written as "Z".
+    DateTime(DateTime<FixedOffset>, u8, bool),
+}
+
+/// The granularity a [`W3CDateTime`] is given in.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Precision {
+    Year,
+    Month,
+    Day,
+    Minute,
+    Second,
+    /// A decimal fraction of a second, with this many digits.
+    Fraction(u8),
 }
 
 impl W3CDateTime {
     /// Create a new W3CDateTime by parsing a string.
-    /// "YYYY-MM-DD" formatted strings will return a
-    /// `W3CDateTime::Date`. "YYYY-MM-DDThh:mm:ssTZD"
+    /// "YYYY", "YYYY-MM" and "YYYY-MM-DD" formatted
+    /// strings will return a `W3CDateTime::Year`,
+    /// `W3CDateTime::YearMonth` and `W3CDateTime::Date`.
+    /// "YYYY-MM-DDThh:mmTZD" formatted strings will return
+    /// a `W3CDateTime::DateTimeMinutes`. "YYYY-MM-DDThh:mm:ssTZD"
     /// and "YYYY-MM-DDThh:mm:ss.sTZD" formatted strings
     /// will return a `W3CDateTime::DateTime`.
     pub fn new(string: &str) -> Result<W3CDateTime, ParseError> {
         Self::parse(string)
     }
 
+    /// The granularity the value was given in.
+    pub fn precision(&self) -> Precision {
+        match *self {
+            Self::Year(_) => Precision::Year,
+            Self::YearMonth(..) => Precision::Month,
+            Self::Date(_) => Precision::Day,
+            Self::DateTimeMinutes(..) => Precision::Minute,
+            Self::DateTime(_, 0, _) => Precision::Second,
+            Self::DateTime(_, digits, _) => Precision::Fraction(digits),
+        }
+    }
+
+    /// Convert to a UTC instant, the start of the period the value covers. The
+    /// same as [`W3CDateTime::period_start`].
+    pub fn to_utc(self) -> DateTime<Utc> {
+        self.period_start()
+    }
+
+    /// The UTC instant the period the value covers starts at. Years, months and
+    /// dates are taken as starting at midnight UTC.
+    pub fn period_start(&self) -> DateTime<Utc> {
+        match *self {
+            Self::Year(year) => midnight(year, 1, 1),
+            Self::YearMonth(year, month) => midnight(year, month, 1),
+            Self::Date(date) => date.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+            Self::DateTimeMinutes(datetime, _) | Self::DateTime(datetime, _, _) => {
+                datetime.with_timezone(&Utc)
+            }
+        }
+    }
+
+    /// The UTC instant just after the period the value covers, so that "2024-02"
+    /// covers the instants from 2024-02-01T00:00:00Z up to, but not including,
+    /// 2024-03-01T00:00:00Z.
+    pub fn period_end(&self) -> DateTime<Utc> {
+        let start = self.period_start();
+        match *self {
+            Self::Year(year) | Self::YearMonth(year, 12) => midnight(year + 1, 1, 1),
+            Self::YearMonth(year, month) => midnight(year, month + 1, 1),
+            Self::Date(_) => start + Duration::days(1),
+            Self::DateTimeMinutes(..) => start + Duration::minutes(1),
+            Self::DateTime(_, digits, _) => {
+                start + Duration::nanoseconds(10_i64.pow(9 - u32::from(digits.min(9))))
+            }
+        }
+    }
+
+    /// The length of the period the value covers.
+    pub fn period(&self) -> Duration {
+        self.period_end() - self.period_start()
+    }
+
+    /// Check whether an instant falls within the period the value covers.
+    pub fn contains(&self, instant: DateTime<Utc>) -> bool {
+        self.period_start() <= instant && instant < self.period_end()
+    }
+
+    /// The time from the start of `earlier` to the start of this value, which is
+    /// negative if `earlier` starts later.
+    pub fn duration_since(&self, earlier: &W3CDateTime) -> Duration {
+        self.period_start() - earlier.period_start()
+    }
+
+    /// The time from the start of this value to `now`, such as the time since an
+    /// entry was last modified.
+    pub fn age(&self, now: DateTime<Utc>) -> Duration {
+        now - self.period_start()
+    }
+
+    /// The UTC offset in seconds, zero for values without a time.
+    fn offset_seconds(&self) -> i32 {
+        match *self {
+            Self::DateTimeMinutes(datetime, _) | Self::DateTime(datetime, _, _) => {
+                datetime.offset().local_minus_utc()
+            }
+            _ => 0,
+        }
+    }
+
+    fn uses_z(&self) -> bool {
+        match *self {
+            Self::DateTimeMinutes(_, use_z) | Self::DateTime(_, _, use_z) => use_z,
+            _ => false,
+        }
+    }
+
     fn parse(string: &str) -> Result<W3CDateTime, ParseError> {
-        if string.len() == 10 {
-            Ok(W3CDateTime::Date(string.parse::<NaiveDate>()?))
-        } else {
-            Ok(W3CDateTime::DateTime(
-                DateTime::parse_from_rfc3339(string)?,
-                string.contains('.'),
-                string.to_uppercase().ends_with('Z'),
-            ))
+        let bytes = string.as_bytes();
+        match bytes.len() {
+            4 if bytes.iter().all(u8::is_ascii_digit) => {
+                let date = NaiveDate::parse_from_str(&format!("{}-01-01", string), "%Y-%m-%d")?;
+                Ok(W3CDateTime::Year(date.year()))
+            }
+            7 => {
+                let date = NaiveDate::parse_from_str(&format!("{}-01", string), "%Y-%m-%d")?;
+                Ok(W3CDateTime::YearMonth(date.year(), date.month()))
+            }
+            10 => Ok(W3CDateTime::Date(string.parse::<NaiveDate>()?)),
+            // "YYYY-MM-DDThh:mm" followed by a time zone rather than seconds. Input
+            // with a multibyte character there is left for the RFC 3339 parser to reject.
+            len if len > 16
+                && string.is_char_boundary(16)
+                && bytes[13] == b':'
+                && bytes[16] != b':' =>
+            {
+                let with_seconds = format!("{}:00{}", &string[..16], &string[16..]);
+                Ok(W3CDateTime::DateTimeMinutes(
+                    DateTime::parse_from_rfc3339(&with_seconds)?,
+                    ends_with_z(string),
+                ))
+            }
+            _ => {
+                let datetime = DateTime::parse_from_rfc3339(string)?;
+                let digits = match string.find('.') {
+                    Some(dot) => string[dot + 1..]
+                        .bytes()
+                        .take_while(u8::is_ascii_digit)
+                        .count()
+                        .min(9) as u8,
+                    None => 0,
+                };
+                Ok(W3CDateTime::DateTime(datetime, digits, ends_with_z(string)))
+            }
         }
     }
 }
 
+/// Midnight UTC at the start of a day, saturating for years chrono cannot represent.
+fn midnight(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map_or(DateTime::<Utc>::MAX_UTC, |datetime| datetime.and_utc())
+}
+
+impl Ord for W3CDateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.period_start()
+            .cmp(&other.period_start())
+            .then_with(|| self.precision().cmp(&other.precision()))
+            .then_with(|| self.offset_seconds().cmp(&other.offset_seconds()))
+            .then_with(|| self.uses_z().cmp(&other.uses_z()))
+    }
+}
+
+/// Values are equal when they are ordered equally, so that datetimes at the same
+/// instant with different UTC offsets, which chrono considers equal, are not.
+impl PartialEq for W3CDateTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for W3CDateTime {}
+
+impl PartialOrd for W3CDateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<W3CDateTime> for DateTime<Utc> {
+    fn from(value: W3CDateTime) -> Self {
+        value.period_start()
+    }
+}
+
+fn ends_with_z(string: &str) -> bool {
+    string.to_uppercase().ends_with('Z')
+}
+
+/// Write a time zone designator: "Z" for UTC if `use_z`, and "+hh:mm" otherwise.
+fn write_offset(
+    f: &mut fmt::Formatter<'_>,
+    datetime: &DateTime<FixedOffset>,
+    use_z: bool,
+) -> fmt::Result {
+    if use_z && datetime.offset().local_minus_utc() == 0 {
+        f.write_str("Z")
+    } else {
+        write!(f, "{}", datetime.format("%:z"))
+    }
+}
+
+impl From<NaiveDate> for W3CDateTime {
+    fn from(date: NaiveDate) -> Self {
+        W3CDateTime::Date(date)
+    }
+}
+
+/// A datetime is written with as many digits of a fraction of a second as needed,
+/// and with its UTC offset as "+hh:mm".
+impl From<DateTime<FixedOffset>> for W3CDateTime {
+    fn from(datetime: DateTime<FixedOffset>) -> Self {
+        W3CDateTime::DateTime(datetime, fraction_digits(datetime.nanosecond()), false)
+    }
+}
+
+/// A UTC datetime is written with as many digits of a fraction of a second as
+/// needed, and with a "Z" time zone designator.
+impl From<DateTime<Utc>> for W3CDateTime {
+    fn from(datetime: DateTime<Utc>) -> Self {
+        W3CDateTime::DateTime(
+            datetime.fixed_offset(),
+            fraction_digits(datetime.nanosecond()),
+            true,
+        )
+    }
+}
+
+impl TryFrom<&str> for W3CDateTime {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(W3CDateTime::new(value)?)
+    }
+}
+
+impl TryFrom<String> for W3CDateTime {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        W3CDateTime::try_from(value.as_str())
+    }
+}
+
+/// The number of digits needed to write a fraction of a second without trailing zeros.
+fn fraction_digits(nanos: u32) -> u8 {
+    let mut nanos = nanos % 1_000_000_000;
+    if nanos == 0 {
+        return 0;
+    }
+
+    let mut digits = 9;
+    while nanos % 10 == 0 {
+        nanos /= 10;
+        digits -= 1;
+    }
+    digits
+}
+
+/// A W3CDateTime is serialized as the string it is written as in a sitemap.
 impl Serialize for W3CDateTime {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        match *self {
-            Self::Date(ref d) => Ok(serializer.serialize_str(d.to_string().as_str()))?,
-            Self::DateTime(ref dt, _, _) => Ok(serializer.serialize_str(dt.to_string().as_str())?),
-        }
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for W3CDateTime {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        W3CDateTime::new(&string).map_err(de::Error::custom)
     }
 }
 
 impl fmt::Display for W3CDateTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
+            Self::Year(year) => write!(f, "{:04}", year),
+            Self::YearMonth(year, month) => write!(f, "{:04}-{:02}", year, month),
             Self::Date(date) => f.write_str(&date.format("%Y-%m-%d").to_string()),
-            Self::DateTime(datetime, fractional, use_z) => {
-                let formatted = datetime.to_rfc3339_opts(
-                    if fractional {
-                        chrono::SecondsFormat::Millis
-                    } else {
-                        chrono::SecondsFormat::Secs
-                    },
-                    use_z,
-                );
-                f.write_str(&formatted)
+            Self::DateTimeMinutes(datetime, use_z) => {
+                write!(f, "{}", datetime.format("%Y-%m-%dT%H:%M"))?;
+                write_offset(f, &datetime, use_z)
+            }
+            Self::DateTime(datetime, digits, use_z) => {
+                write!(f, "{}", datetime.format("%Y-%m-%dT%H:%M:%S"))?;
+                if digits > 0 {
+                    // A leap second is stored as more than a billion nanoseconds.
+                    let nanos = format!("{:09}", datetime.nanosecond() % 1_000_000_000);
+                    write!(f, ".{}", &nanos[..usize::from(digits.min(9))])?;
+                }
+                write_offset(f, &datetime, use_z)
             }
         }
     }
@@ -89,4 +365,169 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_w3c_year() -> Result<(), ParseError> {
+        let result = W3CDateTime::parse("2024")?;
+
+        assert_eq!(result, W3CDateTime::Year(2024));
+        assert_eq!(result.precision(), Precision::Year);
+        assert_eq!("2024", result.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_w3c_year_month() -> Result<(), ParseError> {
+        let result = W3CDateTime::parse("2024-02")?;
+
+        assert_eq!(result, W3CDateTime::YearMonth(2024, 2));
+        assert_eq!("2024-02", result.to_string());
+        assert!(W3CDateTime::parse("2024-13").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_w3c_minutes() -> Result<(), ParseError> {
+        for date_string in ["2024-02-27T10:30Z", "2024-02-27T10:30+01:00"] {
+            let result = W3CDateTime::parse(date_string)?;
+
+            assert_eq!(result.precision(), Precision::Minute);
+            assert_eq!(date_string, result.to_string());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_w3c_fraction_round_trip() -> Result<(), ParseError> {
+        for date_string in [
+            "2024-02-27T10:30:15.5Z",
+            "2024-02-27T10:30:15.50+00:00",
+            "2024-02-27T10:30:15.123456-05:00",
+        ] {
+            assert_eq!(date_string, W3CDateTime::parse(date_string)?.to_string());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_w3c_invalid() {
+        for date_string in [
+            "24",
+            "2024-2",
+            "2024-02-27T10Z",
+            "2024-02-27T10:30",
+            "2024-02-27T10:3éZ",
+            "2024-02-27T10:30é",
+        ] {
+            assert!(W3CDateTime::parse(date_string).is_err(), "{}", date_string);
+        }
+    }
+
+    #[test]
+    fn test_w3c_period() -> Result<(), ParseError> {
+        let month = W3CDateTime::parse("2024-12")?;
+
+        assert_eq!(month.to_utc(), W3CDateTime::parse("2024-12-01")?.to_utc());
+        assert_eq!(
+            month.period_end(),
+            W3CDateTime::parse("2025-01-01")?.to_utc()
+        );
+        assert_eq!(month.period(), Duration::days(31));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_w3c_ordering() -> Result<(), ParseError> {
+        let mut values = [
+            "2024-01-01T12:00Z",
+            "2024-01-01",
+            "2023-12-31T23:00:00-02:00",
+            "2024",
+            "2024-01-01T00:00:00Z",
+            "2024-01",
+        ]
+        .iter()
+        .map(|s| W3CDateTime::parse(s))
+        .collect::<Result<Vec<_>, _>>()?;
+        values.sort();
+
+        let sorted: Vec<String> = values.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            sorted,
+            vec![
+                "2024",
+                "2024-01",
+                "2024-01-01",
+                "2024-01-01T00:00:00Z",
+                "2023-12-31T23:00:00-02:00",
+                "2024-01-01T12:00Z",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_w3c_ordering_consistent_with_eq() -> Result<(), ParseError> {
+        let z = W3CDateTime::parse("2024-01-01T00:00:00Z")?;
+        let offset = W3CDateTime::parse("2024-01-01T00:00:00+00:00")?;
+
+        assert_ne!(z, offset);
+        assert_ne!(z.cmp(&offset), Ordering::Equal);
+        assert_eq!(z.to_utc(), offset.to_utc());
+
+        for (a, b) in [
+            ("2024-01-01T12:00:00+01:00", "2024-01-01T11:00:00+00:00"),
+            ("2024-01-01T12:00+01:00", "2024-01-01T11:00Z"),
+            ("2024-01-01T11:00:00.5Z", "2024-01-01T11:00:00.50Z"),
+            ("2024-01-01T00:00:00Z", "2024-01-01"),
+        ] {
+            let (a, b) = (W3CDateTime::parse(a)?, W3CDateTime::parse(b)?);
+            assert_eq!(a == b, a.cmp(&b) == Ordering::Equal, "{} and {}", a, b);
+            assert_ne!(a, b);
+        }
+
+        let same = W3CDateTime::parse("2024-01-01T12:00:00+01:00")?;
+        assert_eq!(same, W3CDateTime::parse("2024-01-01T12:00:00+01:00")?);
+        assert_eq!(same.cmp(&same), Ordering::Equal);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_w3c_durations() -> Result<(), ParseError> {
+        let date = W3CDateTime::parse("2024-02-27")?;
+        let later = W3CDateTime::parse("2024-02-28T06:00+01:00")?;
+
+        assert_eq!(later.duration_since(&date), Duration::hours(29));
+        assert_eq!(date.duration_since(&later), Duration::hours(-29));
+        assert_eq!(date.period(), Duration::days(1));
+        assert_eq!(date.age(later.to_utc()), Duration::hours(29));
+        assert!(date.contains(W3CDateTime::parse("2024-02-27T23:59:59Z")?.to_utc()));
+        assert!(!date.contains(date.period_end()));
+        assert_eq!(DateTime::<Utc>::from(date), date.period_start());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_w3c_from_chrono() -> Result<(), ParseError> {
+        let datetime = DateTime::parse_from_rfc3339("2024-02-27T10:30:15.250+01:00")?;
+
+        assert_eq!(
+            W3CDateTime::from(datetime).to_string(),
+            "2024-02-27T10:30:15.25+01:00"
+        );
+        assert_eq!(
+            W3CDateTime::from(datetime.with_timezone(&Utc)).to_string(),
+            "2024-02-27T09:30:15.25Z"
+        );
+
+        Ok(())
+    }
 }
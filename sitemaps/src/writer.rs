@@ -0,0 +1,353 @@
+//! Write sitemaps incrementally, without building them in memory first.
+//!
+//! A [`SitemapWriter`] or [`SiteIndexWriter`] streams a single file entry by entry:
+//!
+//! ```rust
+//! use sitemaps::sitemap::UrlEntry;
+//! use sitemaps::writer::SitemapWriter;
+//!
+//! let mut writer = SitemapWriter::new(Vec::new()).unwrap();
+//! let mut entry = UrlEntry::new();
+//! entry.loc = String::from("https://www.example.com/");
+//! writer.write_entry(&entry).unwrap();
+//! let xml = writer.finish().unwrap();
+//! ```
+//!
+//! A [`SitemapSetWriter`] writes large numbers of URLs as a set of sitemap files plus
+//! a sitemap index:
+//!
+//! ```rust,no_run
+//! use sitemaps::sitemap::UrlEntry;
+//! use sitemaps::writer::SitemapSetWriter;
+//!
+//! let mut writer = SitemapSetWriter::new("public", "https://www.example.com/").unwrap();
+//! for n in 0..100_000 {
+//!     let mut entry = UrlEntry::new();
+//!     entry.loc = format!("https://www.example.com/page/{}", n);
+//!     writer.write_entry(&entry).unwrap();
+//! }
+//! let index = writer.finish().unwrap();
+//! assert_eq!(index.entries.len(), 2);
+//! ```
+
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
+use quick_xml::Writer;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+use crate::error::Error;
+use crate::siteindex::{self, SiteIndex, SitemapEntry};
+use crate::sitemap::{self, Sitemap, UrlEntry};
+use crate::w3c_datetime::W3CDateTime;
+use crate::{Sitemaps, WriteOptions, MAX_ENTRIES, MAX_FILE_SIZE};
+
+/// Writes a `<urlset>` document one [`UrlEntry`] at a time. The XML declaration and
+/// the root start tag, declaring the namespaces of all supported extensions, are
+/// written on creation, and the root is closed by [`finish`](SitemapWriter::finish).
+pub struct SitemapWriter<W: Write> {
+    writer: Writer<W>,
+    root: BytesStart<'static>,
+    entry_count: usize,
+}
+
+impl<W: Write> SitemapWriter<W> {
+    /// Start writing a sitemap to `writer`.
+    pub fn new(writer: W) -> Result<Self, Error> {
+        let mut writer = Writer::new(writer);
+        let root = urlset_element();
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        writer.write_event(Event::Start(root.borrow()))?;
+
+        Ok(Self {
+            writer,
+            root,
+            entry_count: 0,
+        })
+    }
+
+    /// Write a `<url>` element. Returns [`Error::TooManyUrls`] if the sitemap
+    /// already has [`MAX_ENTRIES`] entries.
+    pub fn write_entry(&mut self, url_entry: &UrlEntry) -> Result<(), Error> {
+        if self.entry_count >= MAX_ENTRIES {
+            return Err(Error::TooManyUrls);
+        }
+
+        url_entry.write(&mut self.writer, &self.root)?;
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    /// Write a `<url>` element that has already been serialized.
+    fn write_serialized(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.writer.get_mut().write_all(bytes)?;
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    /// The number of entries written so far.
+    pub fn entry_count(&self) -> usize {
+        self.entry_count
+    }
+
+    /// Close the `<urlset>` element and return the underlying writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.writer
+            .write_event(Event::End(BytesEnd::new(sitemap::ROOT_NAME)))?;
+        Ok(self.writer.into_inner())
+    }
+}
+
+/// The `<urlset>` start tag of the sitemaps written by a [`SitemapWriter`].
+fn urlset_element() -> BytesStart<'static> {
+    Sitemap::new().root_element(true).into_owned()
+}
+
+/// Writes a `<sitemapindex>` document one [`SitemapEntry`] at a time, like a
+/// [`SitemapWriter`].
+pub struct SiteIndexWriter<W: Write> {
+    writer: Writer<W>,
+    root: BytesStart<'static>,
+    entry_count: usize,
+}
+
+impl<W: Write> SiteIndexWriter<W> {
+    /// Start writing a sitemap index to `writer`.
+    pub fn new(writer: W) -> Result<Self, Error> {
+        let mut writer = Writer::new(writer);
+        let root = SiteIndex::new().root_element().into_owned();
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        writer.write_event(Event::Start(root.borrow()))?;
+
+        Ok(Self {
+            writer,
+            root,
+            entry_count: 0,
+        })
+    }
+
+    /// Write a `<sitemap>` element. Returns [`Error::TooManyUrls`] if the index
+    /// already has [`MAX_ENTRIES`] entries.
+    pub fn write_entry(&mut self, entry: &SitemapEntry) -> Result<(), Error> {
+        if self.entry_count >= MAX_ENTRIES {
+            return Err(Error::TooManyUrls);
+        }
+
+        entry.write(&mut self.writer, &self.root)?;
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    /// The number of entries written so far.
+    pub fn entry_count(&self) -> usize {
+        self.entry_count
+    }
+
+    /// Close the `<sitemapindex>` element and return the underlying writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.writer
+            .write_event(Event::End(BytesEnd::new(siteindex::ROOT_NAME)))?;
+        Ok(self.writer.into_inner())
+    }
+}
+
+/// The file name of the sitemap index written by [`SitemapSetWriter::finish`].
+pub const INDEX_FILE_NAME: &str = "sitemap-index.xml";
+
+/// Writes [`UrlEntry`] values one at a time to `sitemap-1.xml`, `sitemap-2.xml`, ...
+/// in a directory, starting a new file whenever the next entry would take the
+/// current one over [`MAX_ENTRIES`] entries or [`MAX_FILE_SIZE`] bytes uncompressed.
+/// [`finish`](SitemapSetWriter::finish) then writes a sitemap index listing the files.
+pub struct SitemapSetWriter {
+    dir: PathBuf,
+    base_url: Url,
+    gzip: bool,
+    max_entries: usize,
+    max_file_size: usize,
+    empty_size: usize,
+    root: BytesStart<'static>,
+    current: Option<ChildFile>,
+    index: SiteIndex,
+}
+
+/// The sitemap file currently being written.
+struct ChildFile {
+    writer: SitemapWriter<Output>,
+    size: usize,
+    last_mod: Option<W3CDateTime>,
+}
+
+enum Output {
+    Plain(BufWriter<File>),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<BufWriter<File>>),
+}
+
+impl SitemapSetWriter {
+    /// Create a writer that puts files in `dir` and links to them in the index
+    /// relative to `base_url`, which should end with a `/`.
+    pub fn new<P: AsRef<Path>>(dir: P, base_url: &str) -> Result<Self, Error> {
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            base_url: Url::parse(base_url)?,
+            gzip: false,
+            max_entries: MAX_ENTRIES,
+            max_file_size: MAX_FILE_SIZE,
+            empty_size: SitemapWriter::new(Vec::new())?.finish()?.len(),
+            root: urlset_element(),
+            current: None,
+            index: SiteIndex::new(),
+        })
+    }
+
+    /// Gzip-compress the sitemap files, naming them `sitemap-N.xml.gz`.
+    /// The size limit still applies to the uncompressed contents.
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self, gzip: bool) -> Self {
+        self.gzip = gzip;
+        self
+    }
+
+    /// Start a new file after fewer entries than [`MAX_ENTRIES`].
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.clamp(1, MAX_ENTRIES);
+        self
+    }
+
+    /// Start a new file at a smaller size in bytes than [`MAX_FILE_SIZE`].
+    pub fn max_file_size(mut self, max_file_size: usize) -> Self {
+        self.max_file_size = max_file_size.min(MAX_FILE_SIZE);
+        self
+    }
+
+    /// Write an entry to the current sitemap file, first starting a new file if
+    /// the entry would not fit in it. Returns [`Error::FileTooLarge`] if the entry
+    /// would not fit even in an empty file.
+    pub fn write_entry(&mut self, url_entry: &UrlEntry) -> Result<(), Error> {
+        let mut writer = Writer::new(Vec::new());
+        url_entry.write(&mut writer, &self.root)?;
+        let bytes = writer.into_inner();
+
+        if self.empty_size + bytes.len() > self.max_file_size {
+            return Err(Error::FileTooLarge);
+        }
+
+        if let Some(ref current) = self.current {
+            if current.writer.entry_count() >= self.max_entries
+                || self.empty_size + current.size + bytes.len() > self.max_file_size
+            {
+                self.close_current()?;
+            }
+        }
+
+        let current = match self.current.take() {
+            Some(current) => current,
+            None => self.open_child()?,
+        };
+        let current = self.current.insert(current);
+
+        current.writer.write_serialized(&bytes)?;
+        current.size += bytes.len();
+        if let Some(last_mod) = url_entry.last_mod {
+            let newer = current.last_mod.map_or(true, |newest| last_mod > newest);
+            if newer {
+                current.last_mod = Some(last_mod);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Close the last sitemap file and write the sitemap index, returning it.
+    pub fn finish(mut self) -> Result<SiteIndex, Error> {
+        self.close_current()?;
+
+        let file = File::create(self.dir.join(INDEX_FILE_NAME))?;
+        self.index
+            .write_to(BufWriter::new(file), WriteOptions::new())?
+            .flush()?;
+
+        Ok(self.index)
+    }
+
+    fn file_name(&self, number: usize) -> String {
+        if self.gzip {
+            format!("sitemap-{}.xml.gz", number)
+        } else {
+            format!("sitemap-{}.xml", number)
+        }
+    }
+
+    fn open_child(&mut self) -> Result<ChildFile, Error> {
+        let file_name = self.file_name(self.index.entries.len() + 1);
+        let file = BufWriter::new(File::create(self.dir.join(&file_name))?);
+
+        let output = if self.gzip {
+            Output::gzip(file)
+        } else {
+            Output::Plain(file)
+        };
+
+        let mut entry = SitemapEntry::new();
+        entry.loc = self.base_url.join(&file_name)?.to_string();
+        self.index.entries.push(entry);
+
+        Ok(ChildFile {
+            writer: SitemapWriter::new(output)?,
+            size: 0,
+            last_mod: None,
+        })
+    }
+
+    fn close_current(&mut self) -> Result<(), Error> {
+        if let Some(current) = self.current.take() {
+            current.writer.finish()?.finish()?;
+
+            if let Some(entry) = self.index.entries.last_mut() {
+                entry.last_mod = current.last_mod;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Output {
+    #[cfg(feature = "gzip")]
+    fn gzip(file: BufWriter<File>) -> Self {
+        Output::Gzip(crate::gzip::encoder(file))
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    fn gzip(file: BufWriter<File>) -> Self {
+        Output::Plain(file)
+    }
+
+    /// Flush everything to the file, writing the gzip trailer if compressing.
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Output::Plain(mut file) => file.flush(),
+            #[cfg(feature = "gzip")]
+            Output::Gzip(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Plain(file) => file.write(buf),
+            #[cfg(feature = "gzip")]
+            Output::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Plain(file) => file.flush(),
+            #[cfg(feature = "gzip")]
+            Output::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+extern crate sitemaps;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use sitemaps::error::Error;
+use sitemaps::hreflang::Alternate;
+use sitemaps::image::Image;
+use sitemaps::siteindex::SitemapEntry;
+use sitemaps::sitemap::{ChangeFreq, Priority, UrlEntry};
+use sitemaps::w3c_datetime::W3CDateTime;
+use url::Url;
+
+#[test]
+fn test_build_url_entry_from_strings() -> Result<(), Error> {
+    let entry = UrlEntry::builder()
+        .loc("https://www.example.com")
+        .last_mod("2024-02-27T10:30Z")
+        .change_freq("Daily")
+        .priority("0.85")
+        .build()?;
+
+    assert_eq!(entry.loc, "https://www.example.com/");
+    assert_eq!(entry.last_mod, Some(W3CDateTime::new("2024-02-27T10:30Z")?));
+    assert_eq!(entry.change_freq, Some(ChangeFreq::Daily));
+    assert_eq!(entry.priority.map(|p| p.to_string()), Some("0.85".into()));
+
+    Ok(())
+}
+
+#[test]
+fn test_build_url_entry_from_types() -> Result<(), Error> {
+    let url = Url::parse("https://www.example.com/about")?;
+    let modified: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 2, 27, 10, 30, 15).unwrap();
+    let entry = UrlEntry::builder()
+        .loc(&url)
+        .last_mod(modified)
+        .change_freq(ChangeFreq::Weekly)
+        .priority(0.5_f32)
+        .build()?;
+
+    assert_eq!(entry.loc, url.as_str());
+    assert_eq!(
+        entry.last_mod.map(|d| d.to_string()),
+        Some("2024-02-27T10:30:15Z".into())
+    );
+    assert_eq!(entry.priority, Some(Priority::new(0.5)?));
+
+    let date = NaiveDate::from_ymd_opt(2024, 2, 27).unwrap();
+    let entry = UrlEntry::builder().loc(url).last_mod(date).build()?;
+    assert_eq!(entry.last_mod, Some(W3CDateTime::Date(date)));
+
+    Ok(())
+}
+
+#[test]
+fn test_build_url_entry_errors() {
+    assert!(matches!(
+        UrlEntry::builder().priority(0.5).build(),
+        Err(Error::MissingLoc)
+    ));
+
+    let error = UrlEntry::builder().loc("not a url").build().unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidElement { element: "loc", .. }
+    ));
+
+    let error = UrlEntry::builder()
+        .loc("https://www.example.com/")
+        .priority(1.5)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid <priority>: Priority must not be higher than 1.0"
+    );
+
+    let error = UrlEntry::builder()
+        .loc("https://www.example.com/")
+        .change_freq("sometimes")
+        .last_mod("yesterday")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Invalid <changefreq>: Invalid change frequency: \"sometimes\""
+    );
+
+    let mut image = Image::new();
+    image.loc = String::from("/relative.png");
+    let error = UrlEntry::builder()
+        .loc("https://www.example.com/")
+        .image(image)
+        .build()
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidElement {
+            element: "image:image",
+            ..
+        }
+    ));
+
+    let error = UrlEntry::builder()
+        .loc("https://www.example.com/")
+        .alternate(Alternate::new("not a language", "https://www.example.com/"))
+        .build()
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidElement {
+            element: "xhtml:link",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_build_url_entry_replaced_values() -> Result<(), Error> {
+    let error = UrlEntry::builder()
+        .loc("https://www.example.com/")
+        .change_freq("sometimes")
+        .last_mod("yesterday")
+        .change_freq("daily")
+        .build()
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidElement {
+            element: "lastmod",
+            ..
+        }
+    ));
+
+    let entry = UrlEntry::builder()
+        .loc("https://www.example.com/")
+        .priority(1.5)
+        .change_freq("sometimes")
+        .priority(0.5)
+        .change_freq(ChangeFreq::Daily)
+        .build()?;
+    assert_eq!(entry.priority, Some(Priority::new(0.5)?));
+    assert_eq!(entry.change_freq, Some(ChangeFreq::Daily));
+
+    Ok(())
+}
+
+#[test]
+fn test_build_sitemap_entry() -> Result<(), Error> {
+    let entry = SitemapEntry::builder()
+        .loc("https://www.example.com/sitemap.xml")
+        .last_mod("2024-02")
+        .build()?;
+    assert_eq!(entry.last_mod, Some(W3CDateTime::YearMonth(2024, 2)));
+
+    assert!(matches!(
+        SitemapEntry::builder()
+            .loc("https://www.example.com/")
+            .last_mod("soon")
+            .build(),
+        Err(Error::InvalidElement {
+            element: "lastmod",
+            ..
+        })
+    ));
+    assert!(matches!(
+        SitemapEntry::builder().build(),
+        Err(Error::MissingLoc)
+    ));
+
+    Ok(())
+}
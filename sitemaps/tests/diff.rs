@@ -0,0 +1,94 @@
+extern crate sitemaps;
+
+use sitemaps::diff::{diff, Field, FieldChange};
+use sitemaps::error::Error;
+use sitemaps::fetch::FileFetcher;
+use sitemaps::resolve::IndexResolver;
+use sitemaps::siteindex::{SiteIndex, SitemapEntry};
+use sitemaps::sitemap::Sitemap;
+use sitemaps::w3c_datetime::W3CDateTime;
+use sitemaps::Sitemaps;
+use std::fs::File;
+use std::io::BufReader;
+
+fn read_sitemap(path: &str) -> Result<Sitemap, Error> {
+    Sitemap::read_from(BufReader::new(File::open(path)?))
+}
+
+#[test]
+fn test_diff_sitemaps() -> Result<(), Error> {
+    let old = read_sitemap("tests/data/diff_old.xml")?;
+    let new = read_sitemap("tests/data/diff_new.xml")?;
+    let diff = old.diff(&new);
+
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].loc, "https://www.example.com/new");
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].loc, "https://www.example.com/old");
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].new.loc, "https://www.example.com/blog");
+    assert_eq!(
+        diff.changed[0].changes,
+        vec![
+            FieldChange {
+                field: Field::LastMod,
+                old: Some(String::from("2024-01-01")),
+                new: Some(String::from("2024-02-01")),
+            },
+            FieldChange {
+                field: Field::ChangeFreq,
+                old: Some(String::from("weekly")),
+                new: Some(String::from("daily")),
+            },
+            FieldChange {
+                field: Field::Priority,
+                old: Some(String::from("0.5")),
+                new: None,
+            },
+        ]
+    );
+
+    assert!(new.diff(&new).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_site_indexes() -> Result<(), Error> {
+    let old = SiteIndex::read_from(BufReader::new(File::open("tests/data/sitemap_index.xml")?))?;
+    let mut new =
+        SiteIndex::read_from(BufReader::new(File::open("tests/data/sitemap_index.xml")?))?;
+    new.entries[0].last_mod = Some(W3CDateTime::new("2024-01-01")?);
+    let mut entry = SitemapEntry::new();
+    entry.loc = String::from("https://www.example.com/sitemap-new.xml");
+    new.entries.push(entry);
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.added.len(), 1);
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].changes[0].field, Field::LastMod);
+
+    Ok(())
+}
+
+#[test]
+fn test_diff_resolved() {
+    let resolve = |location: &str| -> Vec<_> {
+        IndexResolver::new(FileFetcher::new("tests/data/resolve"))
+            .resolve_location(location)
+            .filter_map(Result::ok)
+            .collect()
+    };
+    let old = resolve("sitemap-1.xml");
+    let new = resolve("index.xml");
+    let diff = diff(&old, &new);
+
+    assert_eq!(diff.added.len(), 2);
+    assert_eq!(
+        diff.added[0].sitemap,
+        "https://www.example.com/sitemap-2.xml"
+    );
+    assert!(diff.removed.is_empty());
+    assert!(diff.changed.is_empty());
+}
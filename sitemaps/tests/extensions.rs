@@ -0,0 +1,273 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::siteindex::SiteIndex;
+use sitemaps::sitemap::Sitemap;
+use sitemaps::{Extension, Sitemaps, WriteOptions};
+use std::fs::{self, File};
+use std::io::BufReader;
+
+fn strip_whitespace(s: &str) -> String {
+    s.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[test]
+fn test_sitemap_extensions_round_trip() -> Result<(), Error> {
+    let expected = fs::read_to_string("tests/data/extensions_sitemap.xml")?;
+    let file = File::open("tests/data/extensions_sitemap.xml")?;
+    let sitemap = Sitemap::read_from(BufReader::new(file))?;
+
+    assert_eq!(
+        sitemap.attributes,
+        vec![
+            (
+                String::from("xmlns:pagemap"),
+                String::from("http://www.google.com/schemas/sitemap-pagemap/1.0")
+            ),
+            (
+                String::from("xmlns:image"),
+                String::from("http://www.google.com/schemas/sitemap-image/1.1")
+            ),
+        ]
+    );
+    assert_eq!(sitemap.entries[0].images.len(), 1);
+    assert_eq!(sitemap.entries[0].extensions.len(), 1);
+    assert!(sitemap.entries[0].extensions[0]
+        .xml
+        .starts_with("<pagemap:PageMap>"));
+    assert_eq!(
+        sitemap.entries[0].extensions[0].namespaces,
+        vec![(
+            String::from("xmlns:pagemap"),
+            String::from("http://www.google.com/schemas/sitemap-pagemap/1.0")
+        )]
+    );
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert_eq!(strip_whitespace(result), strip_whitespace(&expected));
+    Ok(())
+}
+
+#[test]
+fn test_site_index_extensions_round_trip() -> Result<(), Error> {
+    let expected = fs::read_to_string("tests/data/extensions_sitemap_index.xml")?;
+    let file = File::open("tests/data/extensions_sitemap_index.xml")?;
+    let index = SiteIndex::read_from(BufReader::new(file))?;
+
+    assert_eq!(index.attributes.len(), 1);
+    assert_eq!(
+        index.entries[0].extensions,
+        vec![Extension {
+            xml: String::from("<custom:owner team=\"search\">web</custom:owner>"),
+            namespaces: vec![(
+                String::from("xmlns:custom"),
+                String::from("http://www.example.com/schemas/custom")
+            )],
+        }]
+    );
+
+    let written = index.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert_eq!(strip_whitespace(result), strip_whitespace(&expected));
+    Ok(())
+}
+
+#[test]
+fn test_extension_kept_as_written() -> Result<(), Error> {
+    let fragment =
+        "<x:a>one <x:b/> two &amp; <![CDATA[three]]><y:c xmlns:y=\"urn:y\" y:d=\"e\"/></x:a>";
+    let xml = format!(
+        "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" xmlns:x=\"urn:x\">\
+         <url><loc>https://www.example.com/</loc>{}</url></urlset>",
+        fragment
+    );
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+    let extension = &sitemap.entries[0].extensions[0];
+
+    assert_eq!(extension.xml, fragment);
+    assert_eq!(
+        extension.namespaces,
+        vec![(String::from("xmlns:x"), String::from("urn:x"))]
+    );
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+    assert!(result.contains(&format!("</loc>{}</url>", fragment)));
+
+    Ok(())
+}
+
+#[test]
+fn test_extension_moved_to_another_document() -> Result<(), Error> {
+    let xml = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" xmlns:x=\"urn:x\">\
+               <url><loc>https://www.example.com/</loc><x:a x:b=\"c\">one</x:a></url></urlset>";
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+
+    let mut other = Sitemap::new();
+    other.entries.push(sitemap.entries[0].clone());
+    let written = other.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+    assert!(result.contains("<x:a xmlns:x=\"urn:x\" x:b=\"c\">one</x:a>"));
+
+    let reread = Sitemap::read_from(&written[..])?;
+    assert_eq!(reread.entries[0].extensions[0].namespaces, vec![]);
+    assert_eq!(
+        reread.entries[0].extensions[0].xml,
+        "<x:a xmlns:x=\"urn:x\" x:b=\"c\">one</x:a>"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_image_children_round_trip() -> Result<(), Error> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:image="http://www.google.com/schemas/sitemap-image/1.1" xmlns:x="urn:x">
+  <url>
+    <loc>https://www.example.com/</loc>
+    <image:image>
+      <image:loc>https://www.example.com/image.jpg</image:loc>
+      <x:credit>Example</x:credit>
+    </image:image>
+  </url>
+</urlset>"#;
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+    let image = &sitemap.entries[0].images[0];
+
+    assert_eq!(image.loc, "https://www.example.com/image.jpg");
+    assert_eq!(image.extensions[0].xml, "<x:credit>Example</x:credit>");
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert_eq!(strip_whitespace(result), strip_whitespace(xml));
+    Ok(())
+}
+
+#[test]
+fn test_unknown_news_children_round_trip() -> Result<(), Error> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:news="http://www.google.com/schemas/sitemap-news/0.9">
+  <url>
+    <loc>https://www.example.com/</loc>
+    <news:news>
+      <news:publication>
+        <news:name>The Example Times</news:name>
+        <news:language>en</news:language>
+        <news:region>EU</news:region>
+      </news:publication>
+      <news:title>A story</news:title>
+    </news:news>
+  </url>
+</urlset>"#;
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+    let news = sitemap.entries[0].news.as_ref().unwrap();
+
+    assert_eq!(news.title, "A story");
+    assert!(news.extensions.is_empty());
+    assert_eq!(
+        news.publication.extensions[0].xml,
+        "<news:region>EU</news:region>"
+    );
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert_eq!(strip_whitespace(result), strip_whitespace(xml));
+    Ok(())
+}
+
+#[test]
+fn test_image_prefix_bound_to_another_namespace() -> Result<(), Error> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:image="urn:not-image" xmlns:i="http://www.google.com/schemas/sitemap-image/1.1">
+  <url>
+    <loc>https://www.example.com/</loc>
+    <i:image>
+      <i:loc>https://www.example.com/image.jpg</i:loc>
+    </i:image>
+    <image:photo>Not an image sitemap element</image:photo>
+  </url>
+</urlset>"#;
+    let mut sitemap = Sitemap::read_from(xml.as_bytes())?;
+
+    assert_eq!(sitemap.entries[0].images.len(), 1);
+    assert_eq!(sitemap.entries[0].extensions.len(), 1);
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert_eq!(strip_whitespace(result), strip_whitespace(xml));
+
+    sitemap.attributes.retain(|(key, _)| key != "xmlns:i");
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert!(result.contains(r#"xmlns:image2="http://www.google.com/schemas/sitemap-image/1.1""#));
+    assert!(result.contains("<image2:loc>https://www.example.com/image.jpg</image2:loc>"));
+
+    let sitemap = Sitemap::read_from(written.as_slice())?;
+
+    assert_eq!(sitemap.entries[0].images.len(), 1);
+    assert_eq!(sitemap.entries[0].extensions.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_video_prefix_bound_to_another_namespace() -> Result<(), Error> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:video="urn:not-video" xmlns:v="http://www.google.com/schemas/sitemap-video/1.1">
+  <url>
+    <loc>https://www.example.com/</loc>
+    <v:video>
+      <v:thumbnail_loc>https://www.example.com/thumbnail.jpg</v:thumbnail_loc>
+      <v:title>A video</v:title>
+      <v:description>A video of an example</v:description>
+      <v:content_loc>https://www.example.com/video.mp4</v:content_loc>
+      <v:uploader info="https://www.example.com/uploader">Example</v:uploader>
+    </v:video>
+    <video:clip>Not a video sitemap element</video:clip>
+  </url>
+</urlset>"#;
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+
+    assert_eq!(sitemap.entries[0].videos.len(), 1);
+    assert_eq!(sitemap.entries[0].extensions.len(), 1);
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert_eq!(strip_whitespace(result), strip_whitespace(xml));
+    Ok(())
+}
+
+#[test]
+fn test_news_prefix_bound_to_another_namespace() -> Result<(), Error> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:news="urn:not-news" xmlns:n="http://www.google.com/schemas/sitemap-news/0.9">
+  <url>
+    <loc>https://www.example.com/</loc>
+    <n:news>
+      <n:publication>
+        <n:name>The Example Times</n:name>
+        <n:language>en</n:language>
+      </n:publication>
+      <n:title>A story</n:title>
+    </n:news>
+    <news:headline>Not a news sitemap element</news:headline>
+  </url>
+</urlset>"#;
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+
+    assert!(sitemap.entries[0].news.is_some());
+    assert_eq!(sitemap.entries[0].extensions.len(), 1);
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert_eq!(strip_whitespace(result), strip_whitespace(xml));
+    Ok(())
+}
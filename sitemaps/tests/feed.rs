@@ -0,0 +1,99 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::feed::read_feed;
+use sitemaps::SitemapsFile;
+use std::fs::File;
+use std::io::BufReader;
+
+#[test]
+fn test_parse_rss() -> Result<(), Error> {
+    let file = File::open("tests/data/rss.xml")?;
+    let reader = BufReader::new(file);
+
+    let sitemap = read_feed(reader)?;
+
+    assert_eq!(sitemap.entries.len(), 2);
+    assert_eq!(sitemap.entries[0].loc, "http://www.example.com/posts/first");
+    assert_eq!(
+        sitemap.entries[0].last_mod.unwrap().to_string(),
+        "2002-09-07T09:42:31+00:00"
+    );
+    assert_eq!(
+        sitemap.entries[1].loc,
+        "http://www.example.com/posts/second"
+    );
+    assert_eq!(
+        sitemap.entries[1].last_mod.unwrap().to_string(),
+        "2003-06-10T04:00:00-05:00"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_atom() -> Result<(), Error> {
+    let file = File::open("tests/data/atom.xml")?;
+    let reader = BufReader::new(file);
+
+    let sitemap = match SitemapsFile::read(reader)? {
+        SitemapsFile::Sitemap(sitemap) => sitemap,
+        _ => unreachable!(),
+    };
+
+    assert_eq!(sitemap.entries.len(), 2);
+    assert_eq!(sitemap.entries[0].loc, "http://www.example.com/posts/first");
+    assert_eq!(
+        sitemap.entries[0].last_mod.unwrap().to_string(),
+        "2003-12-13T18:30:02Z"
+    );
+    assert_eq!(
+        sitemap.entries[1].loc,
+        "http://www.example.com/posts/second"
+    );
+    assert_eq!(
+        sitemap.entries[1].last_mod.unwrap().to_string(),
+        "2004-01-01"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_pub_date() {
+    let xml = "<rss version=\"2.0\"><channel><item>
+<link>http://www.example.com/</link>
+<pubDate>yesterday</pubDate>
+</item></channel></rss>";
+
+    match read_feed(xml.as_bytes()) {
+        Err(Error::ParseError { source, entry, .. }) => {
+            assert!(matches!(*source, Error::W3CDatetimeParseError(_)));
+            assert_eq!(entry, Some(0));
+        }
+        _ => panic!("expected a positioned date error"),
+    }
+}
+
+#[test]
+fn test_empty_dates() -> Result<(), Error> {
+    let xml = "<rss version=\"2.0\"><channel><item>
+<link>http://www.example.com/</link>
+<pubDate></pubDate>
+</item></channel></rss>";
+
+    let sitemap = read_feed(xml.as_bytes())?;
+    assert_eq!(sitemap.entries.len(), 1);
+    assert_eq!(sitemap.entries[0].last_mod, None);
+
+    let xml = "<feed xmlns=\"http://www.w3.org/2005/Atom\"><entry>
+<link href=\"http://www.example.com/\"/>
+<updated> </updated>
+</entry></feed>";
+
+    let sitemap = read_feed(xml.as_bytes())?;
+    assert_eq!(sitemap.entries.len(), 1);
+    assert_eq!(sitemap.entries[0].last_mod, None);
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+extern crate sitemaps;
+
+use sitemaps::diff::Field;
+use sitemaps::error::Error;
+use sitemaps::filter::Filter;
+use sitemaps::siteindex::SiteIndex;
+use sitemaps::sitemap::{ChangeFreq, Priority, Sitemap, UrlEntry};
+use sitemaps::w3c_datetime::W3CDateTime;
+use sitemaps::Sitemaps;
+use std::fs::File;
+use std::io::BufReader;
+
+fn read_sitemap(path: &str) -> Result<Sitemap, Error> {
+    Sitemap::read_from(BufReader::new(File::open(path)?))
+}
+
+fn locs(sitemap: &Sitemap) -> Vec<&str> {
+    sitemap.entries.iter().map(|e| e.loc.as_str()).collect()
+}
+
+fn entry(loc: &str, last_mod: Option<&str>) -> Result<UrlEntry, Error> {
+    let mut entry = UrlEntry::new();
+    entry.loc = loc.to_string();
+    entry.last_mod = last_mod.map(W3CDateTime::new).transpose()?;
+    Ok(entry)
+}
+
+#[test]
+fn test_filter_empty_matches_all() -> Result<(), Error> {
+    let mut sitemap = read_sitemap("tests/data/diff_new.xml")?;
+    sitemap.retain(&Filter::new());
+    assert_eq!(sitemap.entries.len(), 4);
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_since_until() -> Result<(), Error> {
+    let mut sitemap = read_sitemap("tests/data/diff_new.xml")?;
+    sitemap.retain(&Filter::new().since(W3CDateTime::new("2024-01-15")?));
+    assert_eq!(
+        locs(&sitemap),
+        vec![
+            "https://www.example.com/blog",
+            "https://www.example.com/new"
+        ]
+    );
+
+    let mut sitemap = read_sitemap("tests/data/diff_new.xml")?;
+    sitemap.retain(&Filter::new().until(W3CDateTime::new("2024-01-01")?));
+    assert_eq!(
+        locs(&sitemap),
+        vec!["https://www.example.com/", "https://www.example.com/about"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_dates_across_forms() -> Result<(), Error> {
+    let filter = Filter::new()
+        .since(W3CDateTime::new("2024-01-01")?)
+        .until(W3CDateTime::new("2024-01-01")?);
+
+    // A date-only bound covers the whole day.
+    assert!(filter.matches(&entry(
+        "https://www.example.com/",
+        Some("2024-01-01T00:00:00Z")
+    )?));
+    assert!(filter.matches(&entry(
+        "https://www.example.com/",
+        Some("2024-01-01T23:59:59Z")
+    )?));
+    assert!(!filter.matches(&entry(
+        "https://www.example.com/",
+        Some("2024-01-02T00:00:00Z")
+    )?));
+    // Datetimes are compared in UTC.
+    assert!(!filter.matches(&entry(
+        "https://www.example.com/",
+        Some("2024-01-01T01:00:00+02:00")
+    )?));
+
+    let filter = Filter::new().until(W3CDateTime::new("2024-01-01T12:00:00Z")?);
+    assert!(filter.matches(&entry("https://www.example.com/", Some("2024-01-01"))?));
+    assert!(!filter.matches(&entry("https://www.example.com/", Some("2024-01-02"))?));
+
+    // Entries without a lastmod don't match a date range.
+    assert!(!filter.matches(&entry("https://www.example.com/", None)?));
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_loc() -> Result<(), Error> {
+    let mut sitemap = read_sitemap("tests/data/diff_new.xml")?;
+    sitemap.retain(&Filter::new().loc_glob("*/blog").loc_glob("*/ne?"));
+    assert_eq!(
+        locs(&sitemap),
+        vec![
+            "https://www.example.com/blog",
+            "https://www.example.com/new"
+        ]
+    );
+
+    let filter = Filter::new().host("WWW.example.com").path_prefix("/blog/");
+    assert!(filter.matches(&entry("https://www.example.com/blog/post", None)?));
+    assert!(!filter.matches(&entry("https://www.example.com/blog", None)?));
+    assert!(!filter.matches(&entry("https://example.com/blog/post", None)?));
+
+    Ok(())
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_filter_regex() -> Result<(), Error> {
+    let mut sitemap = read_sitemap("tests/data/diff_new.xml")?;
+    sitemap.retain(&Filter::new().loc_regex(r"/(about|new)$")?);
+    assert_eq!(
+        locs(&sitemap),
+        vec![
+            "https://www.example.com/about",
+            "https://www.example.com/new"
+        ]
+    );
+
+    assert!(matches!(
+        Filter::new().loc_regex("("),
+        Err(Error::RegexError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_change_freq_priority() -> Result<(), Error> {
+    let mut sitemap = read_sitemap("tests/data/diff_new.xml")?;
+    sitemap.retain(
+        &Filter::new()
+            .change_freq(ChangeFreq::Daily)
+            .change_freq(ChangeFreq::Weekly),
+    );
+    assert_eq!(
+        locs(&sitemap),
+        vec!["https://www.example.com/", "https://www.example.com/blog"]
+    );
+
+    let mut url = entry("https://www.example.com/", None)?;
+    url.priority = Some(Priority::new(0.5)?);
+    assert!(Filter::new()
+        .min_priority("0.5".parse()?)
+        .max_priority("0.8".parse()?)
+        .matches(&url));
+    assert!(!Filter::new().min_priority("0.6".parse()?).matches(&url));
+    assert!(!Filter::new().max_priority("0.4".parse()?).matches(&url));
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_fields() -> Result<(), Error> {
+    let mut sitemap = read_sitemap("tests/data/diff_new.xml")?;
+    sitemap.retain(
+        &Filter::new()
+            .with_field(Field::ChangeFreq)
+            .without_field(Field::Priority),
+    );
+    assert_eq!(locs(&sitemap), vec!["https://www.example.com/blog"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_site_index() -> Result<(), Error> {
+    let file = File::open("tests/data/sitemap_index.xml")?;
+    let mut index = SiteIndex::read_from(BufReader::new(file))?;
+    index.retain(&Filter::new().since(W3CDateTime::new("2005-01-01")?));
+    assert_eq!(index.entries.len(), 1);
+    assert_eq!(
+        index.entries[0].loc,
+        "http://www.example.com/sitemap2.xml.gz"
+    );
+
+    // Index entries have no priority, so a priority range matches none of them.
+    index.retain(&Filter::new().min_priority(Priority::new(0.0)?));
+    assert!(index.entries.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_coarse_dates() -> Result<(), Error> {
+    let mut sitemap = read_sitemap("tests/data/lastmod_granularity.xml")?;
+    sitemap.retain(
+        &Filter::new()
+            .since(W3CDateTime::new("2024")?)
+            .until(W3CDateTime::new("2024-02-27T09:30Z")?),
+    );
+    assert_eq!(
+        locs(&sitemap),
+        vec![
+            "https://www.example.com/month",
+            "https://www.example.com/date",
+            "https://www.example.com/minutes"
+        ]
+    );
+
+    Ok(())
+}
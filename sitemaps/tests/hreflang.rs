@@ -0,0 +1,160 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::hreflang::Alternate;
+use sitemaps::sitemap::Sitemap;
+use sitemaps::{Sitemaps, WriteOptions};
+use std::fs::{self, File};
+use std::io::BufReader;
+
+fn read_hreflang_sitemap() -> Result<Sitemap, Error> {
+    let file = File::open("tests/data/hreflang_sitemap.xml")?;
+    Sitemap::read_from(BufReader::new(file))
+}
+
+#[test]
+fn test_parse_alternates() -> Result<(), Error> {
+    let sitemap = read_hreflang_sitemap()?;
+
+    assert_eq!(sitemap.entries.len(), 3);
+    assert_eq!(sitemap.entries[0].alternates.len(), 3);
+    assert_eq!(
+        sitemap.entries[0].alternates[1],
+        Alternate::new("de-ch", "https://www.example.de/schweiz-deutsch/page.html")
+    );
+    assert!(sitemap.validate_alternates().issues.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_write_alternates() -> Result<(), Error> {
+    let expected = fs::read_to_string("tests/data/hreflang_sitemap.xml")?
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let sitemap = read_hreflang_sitemap()?;
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written)
+        .unwrap()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn test_write_alternates_prefix_bound_to_another_namespace() -> Result<(), Error> {
+    let mut sitemap = read_hreflang_sitemap()?;
+    for (key, value) in &mut sitemap.attributes {
+        if key == "xmlns:xhtml" {
+            *value = String::from("urn:not-xhtml");
+        }
+    }
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written).unwrap();
+
+    assert!(result.contains(r#"xmlns:xhtml2="http://www.w3.org/1999/xhtml""#));
+    assert!(result.contains("<xhtml2:link "));
+
+    let written = Sitemap::read_from(written.as_slice())?;
+    let expected = read_hreflang_sitemap()?;
+    for (written, expected) in written.entries.iter().zip(&expected.entries) {
+        assert_eq!(written.alternates, expected.alternates);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_hreflang() {
+    for valid in [
+        "de",
+        "de-CH",
+        "zh-Hant",
+        "zh-Hant-TW",
+        "es-419",
+        "x-default",
+    ] {
+        assert!(Alternate::new(valid, "").validate_hreflang().is_ok());
+    }
+
+    for invalid in ["", "german", "de_CH", "de-CH-x", "d3"] {
+        assert!(matches!(
+            Alternate::new(invalid, "").validate_hreflang(),
+            Err(Error::InvalidHreflang(_))
+        ));
+    }
+}
+
+#[test]
+fn test_validate_reciprocity() -> Result<(), Error> {
+    let mut sitemap = read_hreflang_sitemap()?;
+    sitemap.entries[2].alternates.remove(2);
+
+    let report = sitemap.validate_alternates();
+    let issues: Vec<_> = report
+        .issues
+        .iter()
+        .map(|issue| (issue.entry, &issue.error))
+        .collect();
+    assert_eq!(issues.len(), 3, "{:?}", issues);
+    assert!(
+        matches!(issues[0], (Some(0), Error::MissingReturnLink(loc)) if loc == "https://www.example.de/schweiz-deutsch/page.html")
+    );
+    assert!(
+        matches!(issues[1], (Some(1), Error::InconsistentAlternates(loc)) if loc == "https://www.example.de/schweiz-deutsch/page.html")
+    );
+    assert!(
+        matches!(issues[2], (Some(2), Error::InconsistentAlternates(loc)) if loc == "https://www.example.de/deutsch/page.html")
+    );
+
+    let mut sitemap = read_hreflang_sitemap()?;
+    sitemap.entries[1].alternates.remove(0);
+
+    let report = sitemap.validate_alternates();
+    assert!(report
+        .issues
+        .iter()
+        .any(|issue| issue.entry == Some(1) && matches!(issue.error, Error::MissingSelfAlternate)));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_cluster_consistency() -> Result<(), Error> {
+    let mut sitemap = read_hreflang_sitemap()?;
+    // Every URL links back, but the English page lists an extra alternate.
+    sitemap.entries[0].alternates.push(Alternate::new(
+        "fr",
+        "https://www.example.fr/francais/page.html",
+    ));
+    sitemap.entries[2].alternates[0].hreflang = String::from("DE");
+
+    let report = sitemap.validate_alternates();
+    let entries: Vec<_> = report.issues.iter().map(|issue| issue.entry).collect();
+    assert_eq!(entries, vec![Some(0), Some(0), Some(1), Some(2)]);
+    assert!(report
+        .issues
+        .iter()
+        .all(|issue| matches!(issue.error, Error::InconsistentAlternates(_))));
+
+    let mut sitemap = read_hreflang_sitemap()?;
+    sitemap.entries[1].alternates[0].hreflang = String::from("xx_invalid");
+    sitemap.entries[2].alternates[1].hreflang = String::from("also_invalid");
+    let report = sitemap.validate_alternates();
+    assert_eq!(
+        report
+            .issues
+            .iter()
+            .filter(|issue| matches!(issue.error, Error::InvalidHreflang(_)))
+            .count(),
+        2
+    );
+
+    Ok(())
+}
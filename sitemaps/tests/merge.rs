@@ -0,0 +1,114 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::merge::{merge, ConflictPolicy};
+use sitemaps::sitemap::{Priority, Sitemap, UrlEntry};
+use sitemaps::w3c_datetime::W3CDateTime;
+use sitemaps::{Sitemaps, NAMESPACE};
+use std::fs::File;
+use std::io::BufReader;
+
+fn read_sitemap(path: &str) -> Result<Sitemap, Error> {
+    Sitemap::read_from(BufReader::new(File::open(path)?))
+}
+
+fn sitemap(entries: &[(&str, Option<&str>, Option<f32>)]) -> Result<Sitemap, Error> {
+    let mut sitemap = Sitemap::new();
+    for (loc, last_mod, priority) in entries {
+        let mut entry = UrlEntry::new();
+        entry.loc = loc.to_string();
+        entry.last_mod = last_mod.map(W3CDateTime::new).transpose()?;
+        entry.priority = priority.map(Priority::new).transpose()?;
+        sitemap.entries.push(entry);
+    }
+    Ok(sitemap)
+}
+
+#[test]
+fn test_merge_files() -> Result<(), Error> {
+    let old = read_sitemap("tests/data/diff_old.xml")?;
+    let new = read_sitemap("tests/data/diff_new.xml")?;
+    let merged = merge(vec![old, new], ConflictPolicy::default())?;
+
+    let locs: Vec<&str> = merged.entries.iter().map(|e| e.loc.as_str()).collect();
+    assert_eq!(
+        locs,
+        vec![
+            "https://www.example.com",
+            "https://www.example.com/about",
+            "https://www.example.com/old",
+            "https://www.example.com/blog",
+            "https://www.example.com/new",
+        ]
+    );
+    assert_eq!(
+        merged.entries[3].last_mod,
+        Some(W3CDateTime::new("2024-02-01")?)
+    );
+    assert_eq!(merged.namespace, NAMESPACE);
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_newest_last_mod() -> Result<(), Error> {
+    let first = sitemap(&[
+        ("https://www.example.com/a", Some("2024-01-02"), None),
+        ("https://www.example.com/b", None, None),
+    ])?;
+    let second = sitemap(&[
+        (
+            "https://www.example.com/a",
+            Some("2024-01-01T23:00:00-02:00"),
+            None,
+        ),
+        ("https://www.example.com/b", Some("2023-01-01"), None),
+    ])?;
+    let merged = merge(vec![first, second], ConflictPolicy::NewestLastMod)?;
+
+    assert_eq!(
+        merged.entries[0].last_mod,
+        Some(W3CDateTime::new("2024-01-01T23:00:00-02:00")?)
+    );
+    assert_eq!(
+        merged.entries[1].last_mod,
+        Some(W3CDateTime::new("2023-01-01")?)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_highest_priority() -> Result<(), Error> {
+    let first = sitemap(&[("https://www.example.com/", None, Some(0.5))])?;
+    let second = sitemap(&[("https://www.example.com/", None, Some(0.8))])?;
+    let third = sitemap(&[("https://www.example.com/", None, None)])?;
+    let merged = merge(vec![first, second, third], ConflictPolicy::HighestPriority)?;
+
+    assert_eq!(merged.entries.len(), 1);
+    assert_eq!(merged.entries[0].priority, Some(Priority::new(0.8)?));
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_first_and_error() -> Result<(), Error> {
+    let mut first = sitemap(&[("https://www.example.com/", Some("2023-01-01"), None)])?;
+    let second = sitemap(&[("https://www.example.com/", Some("2024-01-01"), None)])?;
+    let third = sitemap(&[("https://www.example.com", None, None)])?;
+
+    first.merge(second, ConflictPolicy::First)?;
+    assert_eq!(first.entries.len(), 1);
+    assert_eq!(
+        first.entries[0].last_mod,
+        Some(W3CDateTime::new("2023-01-01")?)
+    );
+
+    assert!(matches!(
+        first.merge(third, ConflictPolicy::Error),
+        Err(Error::DuplicateLoc(ref loc)) if loc == "https://www.example.com"
+    ));
+    assert_eq!(first.entries.len(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+extern crate sitemaps;
+
+use chrono::prelude::*;
+use sitemaps::error::Error;
+use sitemaps::news::News;
+use sitemaps::sitemap::{Sitemap, UrlEntry};
+use sitemaps::{Sitemaps, WriteOptions};
+use std::fs::{self, File};
+use std::io::BufReader;
+
+fn read_news_sitemap() -> Result<Sitemap, Error> {
+    let file = File::open("tests/data/news_sitemap.xml")?;
+    Sitemap::read_from(BufReader::new(file))
+}
+
+#[test]
+fn test_parse_news() -> Result<(), Error> {
+    let sitemap = read_news_sitemap()?;
+
+    let news = sitemap.entries[0].news.as_ref().unwrap();
+    assert_eq!(news.publication.name, "The Example Times");
+    assert_eq!(news.publication.language, "en");
+    assert_eq!(news.publication_date.unwrap().to_string(), "2008-12-23");
+    assert_eq!(news.title, "Companies A, B in Merger Talks");
+    assert_eq!(news.extensions.len(), 1);
+    assert_eq!(
+        news.extensions[0].xml,
+        "<news:keywords>business, merger, acquisition</news:keywords>"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_write_news() -> Result<(), Error> {
+    let expected = fs::read_to_string("tests/data/news_sitemap.xml")?
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let sitemap = read_news_sitemap()?;
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written)
+        .unwrap()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn test_validate_news_age() -> Result<(), Error> {
+    let sitemap = read_news_sitemap()?;
+
+    let now = Utc.with_ymd_and_hms(2008, 12, 24, 12, 0, 0).unwrap();
+    assert!(sitemap.validate_news(now).is_ok());
+
+    let now = Utc.with_ymd_and_hms(2008, 12, 26, 0, 0, 1).unwrap();
+    match sitemap.validate_news(now) {
+        Err(Error::InvalidEntry { source, entry }) => {
+            assert!(matches!(*source, Error::NewsArticleTooOld(_)));
+            assert_eq!(entry, 0);
+        }
+        _ => panic!("expected a NewsArticleTooOld error"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_news_rules() -> Result<(), Error> {
+    let now = Utc.with_ymd_and_hms(2008, 12, 24, 0, 0, 0).unwrap();
+    let mut news = read_news_sitemap()?.entries[0].news.clone().unwrap();
+
+    news.publication.language = String::from("English");
+    assert!(matches!(
+        news.validate(now),
+        Err(Error::InvalidNewsLanguage(_))
+    ));
+
+    news.publication.language = String::from("zh-tw");
+    assert!(news.validate(now).is_ok());
+
+    assert!(matches!(
+        News::new().validate(now),
+        Err(Error::MissingNewsField("name"))
+    ));
+
+    let mut url = UrlEntry::new();
+    url.news = Some(news);
+    let mut sitemap = Sitemap::new();
+    sitemap.entries = vec![url; 1_001];
+    assert!(matches!(
+        sitemap.validate_news(now),
+        Err(Error::TooManyNewsUrls)
+    ));
+
+    Ok(())
+}
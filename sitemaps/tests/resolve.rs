@@ -0,0 +1,140 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::fetch::{Fetcher, FileFetcher};
+use sitemaps::resolve::{resolve_index, IndexResolver, ResolvedEntry};
+use sitemaps::siteindex::SiteIndex;
+use sitemaps::Sitemaps;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+fn locs(results: &[Result<ResolvedEntry, Error>]) -> Vec<String> {
+    results
+        .iter()
+        .filter_map(|r| r.as_ref().ok())
+        .map(|r| r.entry.loc.clone())
+        .collect()
+}
+
+#[test]
+fn test_resolve_index() -> Result<(), Error> {
+    let file = File::open("tests/data/resolve/index.xml")?;
+    let index = SiteIndex::read_from(BufReader::new(file))?;
+    let fetcher = FileFetcher::new("tests/data/resolve");
+    let results: Vec<_> = resolve_index(fetcher, &index).collect();
+
+    assert_eq!(
+        locs(&results),
+        vec![
+            "https://www.example.com/1/a",
+            "https://www.example.com/1/b",
+            "https://www.example.com/2/a",
+            "https://www.example.com/2/b",
+        ]
+    );
+    assert_eq!(
+        results[0].as_ref().unwrap().sitemap,
+        "https://www.example.com/sitemap-1.xml"
+    );
+    assert!(
+        matches!(results[2], Err(Error::NestedSiteIndex(ref loc)) if loc == "https://www.example.com/nested.xml")
+    );
+    assert!(
+        matches!(results[3], Err(Error::InvalidSitemap { ref sitemap, .. }) if sitemap == "https://www.example.com/missing.xml")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_resolve_nested_with_cycle() {
+    let fetcher = FileFetcher::new("tests/data/resolve");
+    let results: Vec<_> = IndexResolver::new(fetcher)
+        .max_depth(2)
+        .resolve_location("https://www.example.com/index.xml")
+        .collect();
+
+    assert_eq!(
+        locs(&results),
+        vec![
+            "https://www.example.com/1/a",
+            "https://www.example.com/1/b",
+            "https://www.example.com/3/a",
+            "https://www.example.com/3/b",
+            "https://www.example.com/2/a",
+            "https://www.example.com/2/b",
+        ]
+    );
+    assert!(
+        matches!(results[4], Err(Error::SitemapCycle(ref loc)) if loc == "https://www.example.com/index.xml")
+    );
+    assert!(matches!(results[5], Err(Error::InvalidSitemap { .. })));
+}
+
+#[test]
+fn test_resolve_max_urls() {
+    let fetcher = FileFetcher::new("tests/data/resolve");
+    let results: Vec<_> = IndexResolver::new(fetcher)
+        .max_depth(2)
+        .max_urls(3)
+        .resolve_location("https://www.example.com/index.xml")
+        .collect();
+
+    // The budget runs out partway through the second sitemap, before the cycle back
+    // to the index or the missing sitemap are reached.
+    assert_eq!(
+        locs(&results),
+        vec![
+            "https://www.example.com/1/a",
+            "https://www.example.com/1/b",
+            "https://www.example.com/3/a",
+        ]
+    );
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(
+        results[2].as_ref().unwrap().sitemap,
+        "https://www.example.com/sitemap-3.xml"
+    );
+}
+
+/// Serves a sitemap whose first entry can be read but whose input then fails.
+struct FailingFetcher;
+
+struct FailingReader;
+
+impl Read for FailingReader {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::Other, "connection reset"))
+    }
+}
+
+impl Fetcher for FailingFetcher {
+    fn fetch(&mut self, _location: &str) -> Result<Box<dyn BufRead>, Error> {
+        let start = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">
+<url><loc>https://www.example.com/a</loc></url>";
+        Ok(Box::new(BufReader::new(
+            Cursor::new(start.as_bytes()).chain(FailingReader),
+        )))
+    }
+}
+
+#[test]
+fn test_resolve_streams_sitemaps() {
+    let results: Vec<_> = IndexResolver::new(FailingFetcher)
+        .max_urls(1)
+        .resolve_location("https://www.example.com/sitemap.xml")
+        .collect();
+
+    assert_eq!(locs(&results), vec!["https://www.example.com/a"]);
+    assert!(results.iter().all(Result::is_ok));
+
+    let results: Vec<_> = IndexResolver::new(FailingFetcher)
+        .resolve_location("https://www.example.com/sitemap.xml")
+        .collect();
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(locs(&results), vec!["https://www.example.com/a"]);
+    assert!(
+        matches!(results[1], Err(Error::InvalidSitemap { ref sitemap, .. }) if sitemap == "https://www.example.com/sitemap.xml")
+    );
+}
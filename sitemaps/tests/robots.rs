@@ -0,0 +1,181 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::fetch::FileFetcher;
+use sitemaps::robots::{discover, robots_location, Robots, Rule, RuleKind};
+use sitemaps::sitemap::{Sitemap, UrlEntry};
+use sitemaps::{Sitemaps, SitemapsFile};
+use std::fs::File;
+use std::io::BufReader;
+
+#[test]
+fn test_parse_robots() -> Result<(), Error> {
+    let file = File::open("tests/data/robots.txt")?;
+    let robots = Robots::read(BufReader::new(file))?;
+
+    assert_eq!(
+        robots.sitemaps,
+        vec![
+            "https://www.example.com/example_1_url.xml",
+            "https://www.example.com/sitemap_index.xml",
+            "https://www.example.com/missing.xml",
+        ]
+    );
+    assert_eq!(robots.groups.len(), 2);
+    assert_eq!(robots.groups[0].user_agents, vec!["Googlebot", "Bingbot"]);
+    assert_eq!(
+        robots.groups[0].rules,
+        vec![
+            Rule {
+                kind: RuleKind::Disallow,
+                path: String::from("/private/")
+            },
+            Rule {
+                kind: RuleKind::Allow,
+                path: String::from("/private/public.html")
+            },
+        ]
+    );
+    assert_eq!(robots.groups[1].user_agents, vec!["*"]);
+    assert_eq!(robots.groups[1].rules.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_rules_without_user_agent() {
+    let robots = Robots::parse("Disallow: /\nSitemap: https://www.example.com/sitemap.xml");
+
+    assert!(robots.groups.is_empty());
+    assert_eq!(robots.sitemaps.len(), 1);
+}
+
+#[test]
+fn test_parse_user_agents_around_other_lines() {
+    let robots = Robots::parse(
+        "User-agent: a\nSitemap: https://www.example.com/sitemap.xml\nCrawl-delay: 10\nUser-agent: b\nDisallow: /x",
+    );
+
+    assert_eq!(robots.groups.len(), 1);
+    assert_eq!(robots.groups[0].user_agents, vec!["a", "b"]);
+    assert_eq!(robots.groups[0].rules.len(), 1);
+    assert_eq!(robots.sitemaps.len(), 1);
+}
+
+#[test]
+fn test_robots_location() -> Result<(), Error> {
+    assert_eq!(
+        robots_location("https://www.example.com/blog/")?,
+        "https://www.example.com/robots.txt"
+    );
+    assert_eq!(
+        robots_location("https://www.example.com/robots.txt")?,
+        "https://www.example.com/robots.txt"
+    );
+    assert_eq!(robots_location("tests/data")?, "tests/data/robots.txt");
+
+    Ok(())
+}
+
+#[test]
+fn test_discover() -> Result<(), Error> {
+    let discovery = discover(FileFetcher::new("tests/data"), "robots.txt")?;
+
+    assert_eq!(discovery.sitemaps.len(), 3);
+    assert!(matches!(
+        discovery.sitemaps[0].file,
+        Ok(SitemapsFile::Sitemap(_))
+    ));
+    assert!(matches!(
+        discovery.sitemaps[1].file,
+        Ok(SitemapsFile::SiteIndex(_))
+    ));
+    assert!(matches!(discovery.sitemaps[2].file, Err(Error::IoError(_))));
+
+    Ok(())
+}
+
+#[test]
+fn test_rules_for_user_agent() -> Result<(), Error> {
+    let file = File::open("tests/data/robots.txt")?;
+    let robots = Robots::read(BufReader::new(file))?;
+
+    assert_eq!(robots.rules_for("Googlebot/2.1").len(), 2);
+    assert_eq!(robots.rules_for("googlebot-image").len(), 2);
+    assert_eq!(robots.rules_for("DuckDuckBot").len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_is_allowed() {
+    let robots = Robots::parse(
+        "User-agent: *
+Disallow: /private/
+Allow: /private/public.html
+Disallow: /*.pdf$
+Disallow: /search?q=
+Allow: /page
+Disallow: /*.php
+",
+    );
+
+    let cases = [
+        ("https://www.example.com/", true),
+        ("https://www.example.com/private/", false),
+        ("https://www.example.com/private/page.html", false),
+        ("https://www.example.com/private/public.html", true),
+        ("https://www.example.com/files/report.pdf", false),
+        ("https://www.example.com/files/report.pdf?download=1", true),
+        ("https://www.example.com/search?q=shoes", false),
+        ("https://www.example.com/search", true),
+        ("https://www.example.com/page", true),
+        ("https://www.example.com/page.php", false),
+        ("https://www.example.com/index.php?page=1", false),
+        ("https://www.example.com/robots.txt", true),
+    ];
+    for (loc, allowed) in cases {
+        assert_eq!(robots.is_allowed("Googlebot", loc), allowed, "{}", loc);
+    }
+}
+
+#[test]
+fn test_is_allowed_tie() {
+    let robots = Robots::parse("User-agent: *\nAllow: /$\nDisallow: /\n");
+
+    assert!(robots.is_allowed("Googlebot", "https://www.example.com/"));
+    assert!(!robots.is_allowed("Googlebot", "https://www.example.com/page"));
+}
+
+#[test]
+fn test_validate_robots() -> Result<(), Error> {
+    let file = File::open("tests/data/robots.txt")?;
+    let robots = Robots::read(BufReader::new(file))?;
+
+    let mut sitemap = Sitemap::new();
+    for loc in [
+        "https://www.example.com/",
+        "https://www.example.com/private/secret.html",
+        "https://www.example.com/guide.pdf",
+    ] {
+        let mut entry = UrlEntry::new();
+        entry.loc = loc.to_string();
+        sitemap.entries.push(entry);
+    }
+
+    let googlebot: Vec<Option<usize>> = sitemap
+        .validate_robots(&robots, "Googlebot")
+        .issues
+        .iter()
+        .map(|i| i.entry)
+        .collect();
+    assert_eq!(googlebot, vec![Some(1)]);
+
+    let others = sitemap.validate_robots(&robots, "DuckDuckBot");
+    assert_eq!(others.issues.len(), 1);
+    assert!(
+        matches!(others.issues[0].error, Error::DisallowedByRobots(ref loc) if loc == "https://www.example.com/guide.pdf")
+    );
+
+    Ok(())
+}
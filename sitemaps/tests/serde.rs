@@ -0,0 +1,67 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::sitemap::ChangeFreq;
+use sitemaps::w3c_datetime::W3CDateTime;
+use sitemaps::{Sitemaps, SitemapsFile, WriteOptions};
+use std::fs::File;
+use std::io::BufReader;
+
+const FILES: [&str; 9] = [
+    "tests/data/sitemap.xml",
+    "tests/data/sitemap_index.xml",
+    "tests/data/extensions_sitemap.xml",
+    "tests/data/extensions_sitemap_index.xml",
+    "tests/data/hreflang_sitemap.xml",
+    "tests/data/image_sitemap.xml",
+    "tests/data/news_sitemap.xml",
+    "tests/data/video_sitemap.xml",
+    "tests/data/lastmod_granularity.xml",
+];
+
+fn write(file: &SitemapsFile) -> Result<Vec<u8>, Error> {
+    match file {
+        SitemapsFile::Sitemap(sitemap) => sitemap.write_to(Vec::new(), WriteOptions::new()),
+        SitemapsFile::SiteIndex(index) => index.write_to(Vec::new(), WriteOptions::new()),
+    }
+}
+
+#[test]
+fn test_xml_json_xml_round_trip() -> Result<(), Error> {
+    for path in FILES {
+        let file = SitemapsFile::read(BufReader::new(File::open(path)?))?;
+
+        let json = serde_json::to_string(&file).unwrap();
+        let from_json: SitemapsFile = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(from_json, file, "{}", path);
+        assert_eq!(write(&from_json)?, write(&file)?, "{}", path);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_serialize_as_written() -> Result<(), Error> {
+    let last_mod = W3CDateTime::new("2024-02-27T10:30:15.25Z")?;
+    assert_eq!(
+        serde_json::to_string(&last_mod).unwrap(),
+        "\"2024-02-27T10:30:15.25Z\""
+    );
+    assert_eq!(
+        serde_json::to_string(&ChangeFreq::Weekly).unwrap(),
+        "\"weekly\""
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_deserialize_invalid() {
+    assert!(serde_json::from_str::<W3CDateTime>("\"2024-02-30\"").is_err());
+    assert!(serde_json::from_str::<ChangeFreq>("\"fortnightly\"").is_err());
+    assert_eq!(
+        serde_json::from_str::<ChangeFreq>("\"Daily\"").unwrap(),
+        ChangeFreq::Daily
+    );
+}
@@ -1,8 +1,8 @@
 extern crate sitemaps;
 
 use sitemaps::error::Error;
-use sitemaps::siteindex::SiteIndex;
-use sitemaps::Sitemaps;
+use sitemaps::siteindex::{SiteIndex, SiteIndexReader};
+use sitemaps::{Sitemaps, WriteOptions};
 use std::fs::File;
 
 use std::io::BufReader;
@@ -45,7 +45,7 @@ fn test_write_sitemap_index_with_schema() -> Result<(), Error> {
     let sitemap = SiteIndex::read_from(reader)?;
 
     let mut buf = Vec::new();
-    let written = sitemap.write_to(&mut buf)?;
+    let written = sitemap.write_to(&mut buf, WriteOptions::new())?;
     let result = std::str::from_utf8(written)
         .unwrap()
         .chars()
@@ -55,3 +55,22 @@ fn test_write_sitemap_index_with_schema() -> Result<(), Error> {
     assert_eq!(result, expected);
     Ok(())
 }
+
+#[test]
+fn test_site_index_reader() -> Result<(), Error> {
+    let file = File::open("tests/data/sitemap_index.xml")?;
+    let mut reader = SiteIndexReader::new(BufReader::new(file))?;
+
+    assert_eq!(reader.namespace(), sitemaps::NAMESPACE);
+    assert!(reader.schema_location().is_none());
+
+    let first = reader.next().unwrap()?;
+    assert_eq!(first.loc, "http://www.example.com/sitemap1.xml.gz");
+
+    let second = reader.next().unwrap()?;
+    assert_eq!(second.loc, "http://www.example.com/sitemap2.xml.gz");
+
+    assert!(reader.next().is_none());
+
+    Ok(())
+}
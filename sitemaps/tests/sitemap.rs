@@ -1,9 +1,10 @@
 extern crate sitemaps;
 
 use sitemaps::error::Error;
-use sitemaps::sitemap::{ChangeFreq, Priority, Sitemap, UrlEntry};
+use sitemaps::image::Image;
+use sitemaps::sitemap::{ChangeFreq, Priority, Sitemap, SitemapReader, UrlEntry};
 use sitemaps::w3c_datetime::W3CDateTime;
-use sitemaps::{Sitemaps, NAMESPACE};
+use sitemaps::{Sitemaps, WriteOptions, NAMESPACE};
 use std::fs::{self, File};
 use std::io::BufReader;
 
@@ -43,7 +44,7 @@ fn test_parse_one_happy() -> Result<(), Error> {
         Some(W3CDateTime::Date("2005-01-01".parse::<NaiveDate>()?))
     );
     assert_eq!(sitemap.entries[0].change_freq, Some(ChangeFreq::Monthly));
-    assert_eq!(sitemap.entries[0].priority, Some(Priority(0.8)));
+    assert_eq!(sitemap.entries[0].priority, Some(Priority::new(0.8)?));
 
     Ok(())
 }
@@ -65,7 +66,7 @@ fn test_parse_two_happy() -> Result<(), Error> {
         Some(W3CDateTime::Date("2006-01-01".parse::<NaiveDate>()?))
     );
     assert_eq!(sitemap.entries[1].change_freq, Some(ChangeFreq::Weekly));
-    assert_eq!(sitemap.entries[1].priority, Some(Priority(0.5)));
+    assert_eq!(sitemap.entries[1].priority, Some(Priority::new(0.5)?));
 
     Ok(())
 }
@@ -101,7 +102,7 @@ fn test_write_two_happy() -> Result<(), Error> {
     let sitemap = Sitemap::read_from(reader)?;
 
     let mut buf = Vec::new();
-    let written = sitemap.write_to(&mut buf)?;
+    let written = sitemap.write_to(&mut buf, WriteOptions::new())?;
 
     assert_eq!(
         EXPECTED
@@ -124,8 +125,8 @@ fn test_new_sitemap() -> Result<(), Error> {
     let mut url_entry = UrlEntry::new();
     url_entry.loc = String::from("http://www.example.com/");
     url_entry.last_mod = Some(W3CDateTime::new("2005-01-01")?);
-    url_entry.change_freq = Some(ChangeFreq::new(String::from("monthly")));
-    url_entry.priority = Some(Priority(0.8));
+    url_entry.change_freq = Some(ChangeFreq::new(String::from("monthly"))?);
+    url_entry.priority = Some(Priority::new(0.8)?);
 
     urls.push(url_entry);
 
@@ -133,7 +134,7 @@ fn test_new_sitemap() -> Result<(), Error> {
     sitemap.entries = urls;
 
     let mut buf = Vec::new();
-    let written = sitemap.write_to(&mut buf)?;
+    let written = sitemap.write_to(&mut buf, WriteOptions::new())?;
     let result = std::str::from_utf8(written)
         .unwrap()
         .chars()
@@ -161,7 +162,7 @@ fn test_write_with_schema() -> Result<(), Error> {
     let sitemap = Sitemap::read_from(reader)?;
 
     let mut buf = Vec::new();
-    let written = sitemap.write_to(&mut buf)?;
+    let written = sitemap.write_to(&mut buf, WriteOptions::new())?;
     let result = std::str::from_utf8(written)
         .unwrap()
         .chars()
@@ -171,3 +172,478 @@ fn test_write_with_schema() -> Result<(), Error> {
     assert_eq!(result, expected);
     Ok(())
 }
+
+#[test]
+fn test_sitemap_reader() -> Result<(), Error> {
+    let file = File::open("tests/data/sitemap.xml")?;
+    let reader = SitemapReader::new(BufReader::new(file))?;
+
+    assert_eq!(reader.namespace(), NAMESPACE);
+    assert!(reader.schema_instance().is_some());
+    assert!(reader.schema_location().is_some());
+
+    let entries = reader.collect::<Result<Vec<UrlEntry>, Error>>()?;
+    let file = File::open("tests/data/sitemap.xml")?;
+    let sitemap = Sitemap::read_from(BufReader::new(file))?;
+
+    assert_eq!(entries, sitemap.entries);
+
+    Ok(())
+}
+
+#[test]
+fn test_sitemap_reader_rejects_sitemap_index() -> Result<(), Error> {
+    let file = File::open("tests/data/sitemap_index.xml")?;
+
+    assert!(matches!(
+        SitemapReader::new(BufReader::new(file)),
+        Err(Error::NotASitemap)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_change_freq_is_positioned() {
+    let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">
+  <url>
+    <loc>http://www.example.com/</loc>
+  </url>
+  <url>
+    <loc>http://www.example.com/about</loc>
+    <changefreq>sometimes</changefreq>
+  </url>
+</urlset>";
+
+    match Sitemap::read_from(xml.as_bytes()) {
+        Err(Error::ParseError {
+            source,
+            position,
+            entry,
+        }) => {
+            assert!(matches!(*source, Error::InvalidChangeFreq(ref value) if value == "sometimes"));
+            assert_eq!(position.line, 8);
+            assert_eq!(entry, Some(1));
+        }
+        _ => panic!("expected a positioned InvalidChangeFreq error"),
+    }
+}
+
+#[test]
+fn test_malformed_xml_is_positioned() {
+    let xml = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">
+  <url>
+    <loc>http://www.example.com/</lastmod>
+  </url>
+</urlset>";
+
+    let mut reader = SitemapReader::new(xml.as_bytes()).unwrap();
+
+    match reader.next() {
+        Some(Err(Error::ParseError {
+            source,
+            position,
+            entry,
+        })) => {
+            assert!(matches!(*source, Error::XmlError(_)));
+            assert_eq!(position.line, 3);
+            assert_eq!(entry, Some(0));
+        }
+        _ => panic!("expected a positioned XML error"),
+    }
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_change_freq_from_str() {
+    assert_eq!("Weekly".parse::<ChangeFreq>().unwrap(), ChangeFreq::Weekly);
+    assert!(matches!(
+        ChangeFreq::try_from("fortnightly"),
+        Err(Error::InvalidChangeFreq(_))
+    ));
+}
+
+#[test]
+fn test_read_write_text() -> Result<(), Error> {
+    let expected = fs::read_to_string("tests/data/example_2_url.txt")?;
+    let file = File::open("tests/data/example_2_url.txt")?;
+
+    let sitemap = Sitemap::read_text(BufReader::new(file))?;
+
+    assert_eq!(sitemap.entries.len(), 2);
+    assert_eq!(sitemap.entries[0].loc, "http://www.example.com/");
+    assert!(sitemap.entries[0].last_mod.is_none());
+
+    let written = sitemap.write_text(Vec::new())?;
+    assert_eq!(std::str::from_utf8(&written).unwrap(), expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_read_text_long_line() {
+    // An endless line is read no further than the size limit.
+    let reader = BufReader::new(std::io::repeat(b'a'));
+
+    match Sitemap::read_text(reader) {
+        Err(Error::ParseError { source, entry, .. }) => {
+            assert!(matches!(*source, Error::FileTooLarge));
+            assert_eq!(entry, Some(0));
+        }
+        _ => panic!("expected a positioned FileTooLarge error"),
+    }
+}
+
+#[test]
+fn test_write_text_validates() {
+    let mut sitemap = Sitemap::new();
+    let mut url_entry = UrlEntry::new();
+    url_entry.loc = String::from("http://www.example.com/");
+    sitemap.entries.push(url_entry.clone());
+    url_entry.loc = String::from("http://www.example.com/a\nhttp://www.example.com/b");
+    sitemap.entries.push(url_entry.clone());
+
+    // A line break in a URL doesn't split it across lines.
+    let written = sitemap.write_text(Vec::new()).unwrap();
+    assert_eq!(
+        std::str::from_utf8(&written).unwrap(),
+        "http://www.example.com/\nhttp://www.example.com/ahttp://www.example.com/b\n"
+    );
+
+    url_entry.loc = String::from("/relative/path");
+    sitemap.entries.push(url_entry.clone());
+    match sitemap.write_text(Vec::new()) {
+        Err(Error::InvalidEntry { source, entry }) => {
+            assert!(matches!(*source, Error::UrlParseError(_)));
+            assert_eq!(entry, 2);
+        }
+        _ => panic!("expected an InvalidEntry error"),
+    }
+
+    url_entry.loc = String::from("http://www.example.com/");
+    sitemap.entries = vec![url_entry; 50_001];
+    assert!(matches!(
+        sitemap.write_text(Vec::new()),
+        Err(Error::TooManyUrls)
+    ));
+}
+
+#[test]
+fn test_read_text_invalid_url() {
+    let text = "http://www.example.com/\n\n/relative/path\n";
+
+    match Sitemap::read_text(text.as_bytes()) {
+        Err(Error::ParseError {
+            source,
+            position,
+            entry,
+        }) => {
+            assert!(matches!(*source, Error::UrlParseError(_)));
+            assert_eq!(position.line, 3);
+            assert_eq!(entry, Some(1));
+        }
+        _ => panic!("expected a positioned UrlParseError"),
+    }
+}
+
+#[test]
+fn test_parse_images() -> Result<(), Error> {
+    let file = File::open("tests/data/image_sitemap.xml")?;
+    let sitemap = Sitemap::read_from(BufReader::new(file))?;
+
+    assert_eq!(sitemap.entries.len(), 2);
+    assert_eq!(sitemap.entries[0].images.len(), 2);
+    assert_eq!(
+        sitemap.entries[0].images[1].loc,
+        "https://example.com/photo.jpg"
+    );
+    assert_eq!(
+        sitemap.entries[0].images[1].caption,
+        Some(String::from("A photo"))
+    );
+    assert_eq!(sitemap.entries[1].images.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_extensions_with_other_prefixes() -> Result<(), Error> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+        xmlns:img="http://www.google.com/schemas/sitemap-image/1.1"
+        xmlns:v="http://www.google.com/schemas/sitemap-video/1.1"
+        xmlns:n="http://www.google.com/schemas/sitemap-news/0.9"
+        xmlns:html="http://www.w3.org/1999/xhtml">
+  <url>
+    <loc>https://example.com/sample.html</loc>
+    <html:link rel="alternate" hreflang="de" href="https://example.com/de/sample.html"/>
+    <img:image>
+      <img:loc>https://example.com/image.jpg</img:loc>
+      <img:caption>An image</img:caption>
+    </img:image>
+    <v:video>
+      <v:thumbnail_loc>https://example.com/thumb.jpg</v:thumbnail_loc>
+      <v:title>A video</v:title>
+      <v:description>About the video</v:description>
+    </v:video>
+    <n:news>
+      <n:publication>
+        <n:name>The Example Times</n:name>
+        <n:language>en</n:language>
+      </n:publication>
+      <n:title>A story</n:title>
+    </n:news>
+    <image:image xmlns:image="urn:not-the-image-extension">
+      <image:loc>https://example.com/other.jpg</image:loc>
+    </image:image>
+  </url>
+</urlset>"#;
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+    let entry = &sitemap.entries[0];
+
+    assert_eq!(entry.alternates.len(), 1);
+    assert_eq!(entry.alternates[0].hreflang, "de");
+    assert_eq!(entry.images.len(), 1);
+    assert_eq!(entry.images[0].loc, "https://example.com/image.jpg");
+    assert_eq!(entry.images[0].caption, Some(String::from("An image")));
+    assert_eq!(entry.videos.len(), 1);
+    assert_eq!(entry.videos[0].title, "A video");
+    let news = entry.news.as_ref().unwrap();
+    assert_eq!(news.publication.name, "The Example Times");
+    assert_eq!(news.title, "A story");
+    assert_eq!(entry.extensions.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_write_images() -> Result<(), Error> {
+    let expected = fs::read_to_string("tests/data/image_sitemap.xml")?
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let file = File::open("tests/data/image_sitemap.xml")?;
+    let sitemap = Sitemap::read_from(BufReader::new(file))?;
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written)
+        .unwrap()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn test_validate_images() {
+    let mut url_entry = UrlEntry::new();
+    url_entry.loc = String::from("http://www.example.com/");
+
+    let mut image = Image::new();
+    image.loc = String::from("http://www.example.com/image.jpg");
+    url_entry.images = vec![image; 1_000];
+    assert!(url_entry.validate_images().is_ok());
+
+    url_entry.images.push(Image::new());
+    assert!(matches!(
+        url_entry.validate_images(),
+        Err(Error::TooManyImages)
+    ));
+
+    url_entry.images.truncate(1);
+    url_entry.images[0].loc = String::from("not a url");
+    assert!(matches!(
+        url_entry.validate_images(),
+        Err(Error::UrlParseError(_))
+    ));
+}
+
+#[test]
+fn test_lastmod_granularity_round_trip() -> Result<(), Error> {
+    let original = fs::read_to_string("tests/data/lastmod_granularity.xml")?;
+    let sitemap = Sitemap::read_from(original.as_bytes())?;
+
+    assert_eq!(sitemap.entries[0].last_mod, Some(W3CDateTime::Year(2023)));
+    assert_eq!(
+        sitemap.entries[1].last_mod,
+        Some(W3CDateTime::YearMonth(2024, 2))
+    );
+
+    let written = String::from_utf8(sitemap.write_to(Vec::new(), WriteOptions::new())?).unwrap();
+    let lastmods = |xml: &str| {
+        xml.split("<lastmod>")
+            .skip(1)
+            .map(|s| s.split("</lastmod>").next().unwrap_or_default().to_string())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(lastmods(&written), lastmods(&original));
+
+    Ok(())
+}
+
+#[test]
+fn test_lastmod_non_ascii_is_an_error() {
+    let xml = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">
+  <url>
+    <loc>http://www.example.com/</loc>
+    <lastmod>2024-02-27T10:3\u{e9}Z</lastmod>
+  </url>
+</urlset>";
+
+    match Sitemap::read_from(xml.as_bytes()) {
+        Err(Error::ParseError { source, entry, .. }) => {
+            assert!(matches!(*source, Error::W3CDatetimeParseError(_)));
+            assert_eq!(entry, Some(0));
+        }
+        _ => panic!("expected a positioned W3CDatetimeParseError"),
+    }
+}
+
+#[test]
+fn test_sort_by_last_mod() -> Result<(), Error> {
+    let mut sitemap = Sitemap::read_from(BufReader::new(File::open(
+        "tests/data/lastmod_granularity.xml",
+    )?))?;
+    sitemap.entries.reverse();
+    sitemap.entries.sort_by_key(|entry| entry.last_mod);
+
+    let locs: Vec<&str> = sitemap.entries.iter().map(|e| e.loc.as_str()).collect();
+    assert_eq!(
+        locs,
+        vec![
+            "https://www.example.com/year",
+            "https://www.example.com/month",
+            "https://www.example.com/date",
+            "https://www.example.com/minutes",
+            "https://www.example.com/seconds",
+            "https://www.example.com/fraction",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_priority_out_of_range_is_read() -> Result<(), Error> {
+    let xml = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">
+  <url>
+    <loc>http://www.example.com/</loc>
+    <priority>1.5</priority>
+  </url>
+  <url>
+    <loc>http://www.example.com/about</loc>
+    <priority>-0.5</priority>
+  </url>
+</urlset>";
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+
+    let priority = sitemap.entries[0].priority.unwrap();
+    assert_eq!(priority.to_string(), "1.5");
+    assert!(matches!(priority.validate(), Err(Error::PriorityTooHigh)));
+    let priority = sitemap.entries[1].priority.unwrap();
+    assert_eq!(priority.to_string(), "-0.5");
+    assert!(matches!(priority.validate(), Err(Error::PriorityTooLow)));
+
+    let written = String::from_utf8(sitemap.write_to(Vec::new(), WriteOptions::new())?).unwrap();
+    assert!(written.contains("<priority>1.5</priority>"));
+    assert!(written.contains("<priority>-0.5</priority>"));
+
+    Ok(())
+}
+
+#[test]
+fn test_priority_keeps_precision() -> Result<(), Error> {
+    for text in ["0.85", "0.50", "1.0", "0", "0.125"] {
+        let priority: Priority = text.parse()?;
+        assert_eq!(priority.to_string(), text);
+    }
+
+    let mut url_entry = UrlEntry::new();
+    url_entry.loc = String::from("http://www.example.com/");
+    url_entry.priority = Some("0.85".parse()?);
+    let mut sitemap = Sitemap::new();
+    sitemap.entries.push(url_entry);
+    let written = String::from_utf8(sitemap.write_to(Vec::new(), WriteOptions::new())?).unwrap();
+    assert!(written.contains("<priority>0.85</priority>"));
+
+    Ok(())
+}
+
+#[test]
+fn test_priority_comparison() -> Result<(), Error> {
+    let half: Priority = "0.5".parse()?;
+    assert_eq!(half, "0.50".parse()?);
+    assert_eq!(half, Priority::new(0.5)?);
+    assert!(half < "0.51".parse()?);
+    assert!(Priority::new(1.0)? > "0.999999999".parse()?);
+    assert_eq!(Priority::new(0.8)?.to_string(), "0.8");
+    assert_eq!(Priority::new(1.0)?.to_string(), "1.0");
+
+    Ok(())
+}
+
+#[test]
+fn test_priority_max_scale_range() -> Result<(), Error> {
+    assert_eq!("1.000000000".parse::<Priority>()?, Priority::new(1.0)?);
+    assert_eq!("0.999999999".parse::<Priority>()?.scale(), 9);
+    for text in ["9.999999999", "5.000000000", "1.000000001", "4.294967296"] {
+        assert!(
+            matches!(text.parse::<Priority>(), Err(Error::PriorityTooHigh)),
+            "{}",
+            text
+        );
+    }
+    assert!(matches!(
+        "-0.000000001".parse::<Priority>(),
+        Err(Error::PriorityTooLow)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_priority_beyond_max_scale() -> Result<(), Error> {
+    let priority: Priority = "0.8500000000".parse()?;
+    assert_eq!(priority, Priority::new(0.85)?);
+    assert_eq!(priority.scale(), Priority::MAX_SCALE);
+    assert_eq!("0.0000000001".parse::<Priority>()?, Priority::new(0.0)?);
+    assert_eq!(
+        "0.1234567895".parse::<Priority>()?.to_string(),
+        "0.123456790"
+    );
+    assert_eq!("0.9999999999".parse::<Priority>()?, Priority::new(1.0)?);
+    assert!(matches!(
+        "1.0000000005".parse::<Priority>(),
+        Err(Error::PriorityTooHigh)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_priority_invalid() {
+    assert!(matches!(Priority::new(1.1), Err(Error::PriorityTooHigh)));
+    assert!(matches!(Priority::new(-0.1), Err(Error::PriorityTooLow)));
+    assert!(matches!(
+        "1.01".parse::<Priority>(),
+        Err(Error::PriorityTooHigh)
+    ));
+    assert!(matches!(
+        "10".parse::<Priority>(),
+        Err(Error::PriorityTooHigh)
+    ));
+    assert!(matches!(
+        "-0.5".parse::<Priority>(),
+        Err(Error::PriorityTooLow)
+    ));
+    for text in ["", ".", "high", "0.5e0", "0.5.0", "--0.5"] {
+        assert!(
+            matches!(text.parse::<Priority>(), Err(Error::InvalidPriority(_))),
+            "{}",
+            text
+        );
+    }
+}
@@ -28,7 +28,7 @@ fn test_parse_sitemap() -> Result<(), Error> {
         Some(W3CDateTime::Date("2005-01-01".parse::<NaiveDate>()?))
     );
     assert_eq!(sitemap.entries[0].change_freq, Some(ChangeFreq::Monthly));
-    assert_eq!(sitemap.entries[0].priority, Some(Priority(0.8)));
+    assert_eq!(sitemap.entries[0].priority, Some(Priority::new(0.8)?));
     Ok(())
 }
 
@@ -51,9 +51,93 @@ fn test_parse_sitemap_index() -> Result<(), Error> {
         sitemap_index.entries[0].last_mod,
         Some(W3CDateTime::DateTime(
             "2004-10-01T18:23:17+00:00".parse::<DateTime<FixedOffset>>()?,
-            false,
+            0,
             false
         ))
     );
     Ok(())
 }
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_parse_gzip_sitemap() -> Result<(), Error> {
+    let file = File::open("tests/data/example_2_url.xml.gz")?;
+    let reader = BufReader::new(file);
+
+    let sitemap = match SitemapsFile::read(reader)? {
+        SitemapsFile::Sitemap(sitemap) => sitemap,
+        _ => unreachable!(),
+    };
+    assert_eq!(sitemap.entries.len(), 2);
+    assert_eq!(sitemap.entries[1].loc, "http://www.examples.com/");
+    Ok(())
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_write_gzip_round_trip() -> Result<(), Error> {
+    use sitemaps::{Sitemaps, WriteOptions};
+
+    let file = File::open("tests/data/example_2_url.xml")?;
+    let sitemap = sitemaps::sitemap::Sitemap::read_from(BufReader::new(file))?;
+
+    let compressed = sitemap.write_to(Vec::new(), WriteOptions::new().gzip(true))?;
+    assert!(compressed.starts_with(&sitemaps::gzip::MAGIC));
+
+    let result = match SitemapsFile::read(&compressed[..])? {
+        SitemapsFile::Sitemap(sitemap) => sitemap,
+        _ => unreachable!(),
+    };
+    assert_eq!(result, sitemap);
+    Ok(())
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_parse_gzip_one_byte_reads() -> Result<(), Error> {
+    // A reader that returns a byte at a time, as a pipe might.
+    let file = File::open("tests/data/example_2_url.xml.gz")?;
+    let reader = BufReader::with_capacity(1, file);
+
+    match SitemapsFile::read(reader)? {
+        SitemapsFile::Sitemap(sitemap) => assert_eq!(sitemap.entries.len(), 2),
+        _ => panic!("expected a sitemap"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_one_byte_reads() -> Result<(), Error> {
+    let xml = "\u{feff}  <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">
+  <url><loc>http://www.example.com/</loc></url>
+</urlset>";
+    let reader = BufReader::with_capacity(1, xml.as_bytes());
+
+    match SitemapsFile::read(reader)? {
+        SitemapsFile::Sitemap(sitemap) => assert_eq!(sitemap.entries.len(), 1),
+        _ => panic!("expected a sitemap"),
+    }
+
+    let reader = BufReader::with_capacity(1, "  http://www.example.com/\n".as_bytes());
+    match SitemapsFile::read(reader)? {
+        SitemapsFile::Sitemap(sitemap) => {
+            assert_eq!(sitemap.entries[0].loc, "http://www.example.com/")
+        }
+        _ => panic!("expected a text sitemap"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_text_sitemap() -> Result<(), Error> {
+    let file = File::open("tests/data/example_2_url.txt")?;
+    let reader = BufReader::new(file);
+
+    let sitemap = match SitemapsFile::read(reader)? {
+        SitemapsFile::Sitemap(sitemap) => sitemap,
+        _ => unreachable!(),
+    };
+    assert_eq!(sitemap.entries.len(), 2);
+    assert_eq!(sitemap.entries[1].loc, "http://www.examples.com/");
+    Ok(())
+}
@@ -0,0 +1,153 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::siteindex::{SiteIndex, SitemapEntry};
+use sitemaps::sitemap::{Sitemap, UrlEntry};
+use sitemaps::validation::{Scope, Severity};
+use sitemaps::{Sitemaps, SitemapsFile, MAX_ENTRIES};
+use std::fs::File;
+use std::io::BufReader;
+
+#[test]
+fn test_validate_sitemap() -> Result<(), Error> {
+    let file = File::open("tests/data/invalid_sitemap.xml")?;
+    let sitemap = SitemapsFile::read(BufReader::new(file))?;
+    let report = sitemap.validate();
+
+    assert!(!report.is_valid());
+    let issues: Vec<String> = report.issues.iter().map(|i| i.to_string()).collect();
+    assert_eq!(
+        issues,
+        vec![
+            "warning: Non-standard namespace: \"http://www.google.com/schemas/sitemap/0.84\"",
+            "error: entry 1: Invalid URL error",
+            "warning: entry 2: Duplicate URL: https://www.example.com/",
+            "error: entry 2: Priority must not be higher than 1.0",
+        ]
+    );
+    assert_eq!(report.errors().count(), 2);
+    assert_eq!(report.warnings().count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_valid_sitemap() -> Result<(), Error> {
+    let file = File::open("tests/data/example_2_url.xml")?;
+    let sitemap = Sitemap::read_from(BufReader::new(file))?;
+    let report = sitemap.validate();
+
+    assert!(report.is_valid());
+    assert!(report.issues.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_too_many_urls() {
+    let mut sitemap = Sitemap::new();
+    for n in 0..=MAX_ENTRIES {
+        let mut entry = UrlEntry::new();
+        entry.loc = format!("https://www.example.com/{}", n);
+        sitemap.entries.push(entry);
+    }
+    let report = sitemap.validate();
+
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].severity, Severity::Error);
+    assert_eq!(report.issues[0].entry, None);
+    assert!(matches!(report.issues[0].error, Error::TooManyUrls));
+}
+
+#[test]
+fn test_validate_site_index() {
+    let mut index = SiteIndex::new();
+    for loc in ["https://www.example.com/sitemap.xml", "sitemap.xml"] {
+        let mut entry = SitemapEntry::new();
+        entry.loc = loc.to_string();
+        index.entries.push(entry);
+    }
+    let report = index.validate();
+
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].entry, Some(1));
+    assert!(matches!(report.issues[0].error, Error::UrlParseError(_)));
+}
+
+fn sitemap_with(locs: &[&str]) -> Sitemap {
+    let mut sitemap = Sitemap::new();
+    for loc in locs {
+        let mut entry = UrlEntry::new();
+        entry.loc = loc.to_string();
+        sitemap.entries.push(entry);
+    }
+    sitemap
+}
+
+#[test]
+fn test_validate_normalized_duplicates() {
+    let sitemap = sitemap_with(&[
+        "https://www.example.com/",
+        "HTTPS://WWW.EXAMPLE.COM:443",
+        "https://www.example.com/page",
+    ]);
+    let report = sitemap.validate();
+
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].severity, Severity::Warning);
+    assert_eq!(report.issues[0].entry, Some(1));
+    assert!(matches!(report.issues[0].error, Error::DuplicateLoc(_)));
+}
+
+#[test]
+fn test_validate_missing_namespace() -> Result<(), Error> {
+    let xml = "<urlset><url><loc>https://www.example.com/</loc></url></urlset>";
+    let sitemap = Sitemap::read_from(xml.as_bytes())?;
+    let report = sitemap.validate();
+
+    assert!(report.is_valid());
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].severity, Severity::Warning);
+    assert!(matches!(&report.issues[0].error, Error::NonStandardNamespace(ns) if ns.is_empty()));
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_scope() -> Result<(), Error> {
+    let sitemap = sitemap_with(&[
+        "http://example.com/catalog/shoes",
+        "http://example.com/catalog/",
+        "http://example.com/about",
+        "https://example.com/catalog/shoes",
+        "http://www.example.com/catalog/shoes",
+        "http://example.com:8080/catalog/shoes",
+        "http://example.com:80/catalog/hats",
+    ]);
+    let scope = Scope::new("http://example.com/catalog/sitemap.xml")?;
+    let report = sitemap.validate_scope(&scope);
+
+    let entries: Vec<Option<usize>> = report.issues.iter().map(|i| i.entry).collect();
+    assert_eq!(entries, vec![Some(2), Some(3), Some(4), Some(5)]);
+    assert!(
+        matches!(report.issues[0].error, Error::OutOfScope(ref loc) if loc == "http://example.com/about")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_validate_scope_cross_submission() -> Result<(), Error> {
+    let sitemap = sitemap_with(&[
+        "http://example.com/catalog/shoes",
+        "http://www.example.org/shoes",
+        "http://www.example.net/shoes",
+    ]);
+    let scope = Scope::new("http://example.com/catalog/")?.allow_host("www.example.org");
+    let report = sitemap.validate_scope(&scope);
+
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].entry, Some(2));
+
+    Ok(())
+}
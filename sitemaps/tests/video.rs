@@ -0,0 +1,119 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::sitemap::Sitemap;
+use sitemaps::video::{Relationship, Video};
+use sitemaps::{Sitemaps, WriteOptions};
+use std::fs::{self, File};
+use std::io::BufReader;
+
+#[test]
+fn test_parse_video() -> Result<(), Error> {
+    let file = File::open("tests/data/video_sitemap.xml")?;
+    let sitemap = Sitemap::read_from(BufReader::new(file))?;
+
+    let video = &sitemap.entries[0].videos[0];
+    assert_eq!(video.title, "Grilling steaks for summer");
+    assert_eq!(video.duration, Some(600));
+    assert_eq!(video.rating, Some(4.2));
+    assert_eq!(video.view_count, Some(12345));
+    assert_eq!(video.family_friendly, Some(true));
+    assert_eq!(video.live, Some(false));
+    assert_eq!(
+        video.expiration_date.unwrap().to_string(),
+        "2021-11-05T19:20:30+08:00"
+    );
+
+    let restriction = video.restriction.as_ref().unwrap();
+    assert_eq!(restriction.relationship, Relationship::Allow);
+    assert_eq!(restriction.values, vec!["IE", "GB", "US", "CA"]);
+
+    let uploader = video.uploader.as_ref().unwrap();
+    assert_eq!(uploader.name, "GrillyMcGrillerson");
+    assert_eq!(
+        uploader.info.as_deref(),
+        Some("https://www.example.com/users/grillymcgrillerson")
+    );
+    assert_eq!(video.tags, vec!["steak", "grilling"]);
+    assert_eq!(video.extensions.len(), 1);
+    assert_eq!(
+        video.extensions[0].xml,
+        "<video:price currency=\"EUR\">1.99</video:price>"
+    );
+    assert!(video.validate().is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_write_video() -> Result<(), Error> {
+    let expected = fs::read_to_string("tests/data/video_sitemap.xml")?
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let file = File::open("tests/data/video_sitemap.xml")?;
+    let sitemap = Sitemap::read_from(BufReader::new(file))?;
+
+    let written = sitemap.write_to(Vec::new(), WriteOptions::new())?;
+    let result = std::str::from_utf8(&written)
+        .unwrap()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    assert_eq!(result, expected);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_video_value() {
+    let xml = "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\"
+xmlns:video=\"http://www.google.com/schemas/sitemap-video/1.1\">
+<url><loc>http://www.example.com/</loc>
+<video:video><video:family_friendly>maybe</video:family_friendly></video:video>
+</url></urlset>";
+
+    match Sitemap::read_from(xml.as_bytes()) {
+        Err(Error::ParseError { source, .. }) => assert!(matches!(
+            *source,
+            Error::InvalidVideoValue {
+                field: "family_friendly",
+                ..
+            }
+        )),
+        _ => panic!("expected an InvalidVideoValue error"),
+    }
+}
+
+#[test]
+fn test_validate_video() {
+    let mut video = Video::new();
+    assert!(matches!(
+        video.validate(),
+        Err(Error::MissingVideoField("thumbnail_loc"))
+    ));
+
+    video.thumbnail_loc = String::from("https://www.example.com/thumbs/123.jpg");
+    video.title = String::from("Title");
+    video.description = String::from("Description");
+    assert!(matches!(
+        video.validate(),
+        Err(Error::MissingVideoField("content_loc"))
+    ));
+
+    video.player_loc = Some(String::from("https://www.example.com/player"));
+    assert!(video.validate().is_ok());
+
+    video.duration = Some(0);
+    assert!(matches!(
+        video.validate(),
+        Err(Error::VideoDurationOutOfRange(0))
+    ));
+
+    video.duration = Some(28_800);
+    video.rating = Some(5.5);
+    assert!(matches!(
+        video.validate(),
+        Err(Error::VideoRatingOutOfRange(_))
+    ));
+}
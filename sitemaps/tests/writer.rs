@@ -0,0 +1,198 @@
+extern crate sitemaps;
+
+use sitemaps::error::Error;
+use sitemaps::siteindex::{SiteIndex, SitemapEntry};
+use sitemaps::sitemap::{Sitemap, UrlEntry};
+use sitemaps::w3c_datetime::W3CDateTime;
+use sitemaps::writer::{SiteIndexWriter, SitemapSetWriter, SitemapWriter, INDEX_FILE_NAME};
+use sitemaps::{Sitemaps, MAX_ENTRIES};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sitemaps-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn url_entry(n: usize) -> UrlEntry {
+    let mut entry = UrlEntry::new();
+    entry.loc = format!("https://www.example.com/page/{}", n);
+    entry
+}
+
+#[test]
+fn test_split_by_entry_count() -> Result<(), Error> {
+    let dir = temp_dir("split-count");
+    let mut writer =
+        SitemapSetWriter::new(&dir, "https://www.example.com/sitemaps/")?.max_entries(2);
+
+    let dates = [
+        "2024-01-01",
+        "2024-03-01T10:00:00+02:00",
+        "2024-02-01",
+        "2023-01-01",
+        "2023-06-01",
+    ];
+    for (n, date) in dates.iter().enumerate() {
+        let mut entry = url_entry(n);
+        entry.last_mod = Some(W3CDateTime::new(date)?);
+        writer.write_entry(&entry)?;
+    }
+    let index = writer.finish()?;
+
+    let locs: Vec<&str> = index.entries.iter().map(|e| e.loc.as_str()).collect();
+    assert_eq!(
+        locs,
+        vec![
+            "https://www.example.com/sitemaps/sitemap-1.xml",
+            "https://www.example.com/sitemaps/sitemap-2.xml",
+            "https://www.example.com/sitemaps/sitemap-3.xml",
+        ]
+    );
+    let last_mods: Vec<String> = index
+        .entries
+        .iter()
+        .map(|e| e.last_mod.unwrap().to_string())
+        .collect();
+    assert_eq!(
+        last_mods,
+        vec!["2024-03-01T10:00:00+02:00", "2024-02-01", "2023-06-01"]
+    );
+
+    let first = Sitemap::read_from(BufReader::new(File::open(dir.join("sitemap-1.xml"))?))?;
+    assert_eq!(first.entries.len(), 2);
+    assert_eq!(first.entries[1].loc, "https://www.example.com/page/1");
+    let last = Sitemap::read_from(BufReader::new(File::open(dir.join("sitemap-3.xml"))?))?;
+    assert_eq!(last.entries.len(), 1);
+
+    let written = SiteIndex::read_from(BufReader::new(File::open(dir.join(INDEX_FILE_NAME))?))?;
+    assert_eq!(written.entries, index.entries);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_split_by_file_size() -> Result<(), Error> {
+    let dir = temp_dir("split-size");
+    let mut writer = SitemapSetWriter::new(&dir, "https://www.example.com/")?.max_file_size(1_000);
+
+    for n in 0..20 {
+        writer.write_entry(&url_entry(n))?;
+    }
+    let index = writer.finish()?;
+
+    assert!(index.entries.len() > 1);
+    let mut total = 0;
+    for n in 1..=index.entries.len() {
+        let path = dir.join(format!("sitemap-{}.xml", n));
+        assert!(fs::metadata(&path)?.len() <= 1_000);
+        total += Sitemap::read_from(BufReader::new(File::open(path)?))?
+            .entries
+            .len();
+    }
+    assert_eq!(total, 20);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_entry_too_large() -> Result<(), Error> {
+    let dir = temp_dir("too-large");
+    let mut writer = SitemapSetWriter::new(&dir, "https://www.example.com/")?.max_file_size(300);
+
+    let mut entry = url_entry(0);
+    entry.loc.push_str(&"a".repeat(300));
+    assert!(matches!(
+        writer.write_entry(&entry),
+        Err(Error::FileTooLarge)
+    ));
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_gzip_set() -> Result<(), Error> {
+    let dir = temp_dir("gzip");
+    let mut writer = SitemapSetWriter::new(&dir, "https://www.example.com/")?
+        .gzip(true)
+        .max_entries(3);
+
+    for n in 0..4 {
+        writer.write_entry(&url_entry(n))?;
+    }
+    let index = writer.finish()?;
+
+    assert_eq!(
+        index.entries[1].loc,
+        "https://www.example.com/sitemap-2.xml.gz"
+    );
+    let file = File::open(dir.join("sitemap-1.xml.gz"))?;
+    match sitemaps::SitemapsFile::read(BufReader::new(file))? {
+        sitemaps::SitemapsFile::Sitemap(sitemap) => assert_eq!(sitemap.entries.len(), 3),
+        sitemaps::SitemapsFile::SiteIndex(_) => panic!("expected a sitemap"),
+    }
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_stream_sitemap() -> Result<(), Error> {
+    let mut writer = SitemapWriter::new(Vec::new())?;
+    for n in 0..3 {
+        writer.write_entry(&url_entry(n))?;
+    }
+    assert_eq!(writer.entry_count(), 3);
+    let written = writer.finish()?;
+
+    let sitemap = Sitemap::read_from(written.as_slice())?;
+    let locs: Vec<&str> = sitemap.entries.iter().map(|e| e.loc.as_str()).collect();
+    assert_eq!(
+        locs,
+        vec![
+            "https://www.example.com/page/0",
+            "https://www.example.com/page/1",
+            "https://www.example.com/page/2",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_stream_sitemap_too_many_urls() -> Result<(), Error> {
+    let mut writer = SitemapWriter::new(std::io::sink())?;
+    let entry = url_entry(0);
+    for _ in 0..MAX_ENTRIES {
+        writer.write_entry(&entry)?;
+    }
+
+    assert!(matches!(
+        writer.write_entry(&entry),
+        Err(Error::TooManyUrls)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_stream_site_index() -> Result<(), Error> {
+    let mut writer = SiteIndexWriter::new(Vec::new())?;
+    let mut entry = SitemapEntry::new();
+    entry.loc = String::from("https://www.example.com/sitemap-1.xml");
+    entry.last_mod = Some(W3CDateTime::new("2024-01-01")?);
+    writer.write_entry(&entry)?;
+    let written = writer.finish()?;
+
+    let index = SiteIndex::read_from(written.as_slice())?;
+    assert_eq!(index.entries, vec![entry]);
+
+    Ok(())
+}